@@ -16,12 +16,33 @@ pub enum Error {
     /// The operation tried to set decimals that were already set.
     DecimalsAlreadySet: (),
 
-    /// The operation would burn more tokens than are available.
-    BurnInsufficientBalance: (),
+    /// The amount of coins forwarded does not match the amount to burn.
+    IncorrectAmountProvided: u64,
+
+    /// The coins forwarded are not the asset being burned.
+    IncorrectAssetProvided: AssetId,
+
+    /// The operation would burn more tokens than have been minted.
+    InsufficientSupply: u64,
+
+    /// The mint would take the asset's supply above its max supply.
+    MaxSupplyReached: u64,
 
     /// The method caller is not authorized.
     Unauthorized: Identity,
 
+    /// The sub-identifier is not reserved for the calling strategy.
+    SubIdNotAssigned: SubId,
+
+    /// The sub-identifier is already reserved for another strategy.
+    SubIdAlreadyAssigned: ContractId,
+
+    /// The mint would take the strategy's outstanding supply above its cap.
+    StrategySupplyCapExceeded: u64,
+
+    /// The contract holds fewer coins than the amount to transfer.
+    InsufficientBalance: u64,
+
     /// The owner is not initialized.
     OwnerNotInitialized: (),
 
@@ -31,6 +52,9 @@ pub enum Error {
     /// The contract is already initialized.
     AlreadyInitialized: (),
     
-    /// The caller is not the owner.
-    NotOwner: (),
+    /// The caller is not the proxy owner.
+    NotProxyOwner: Identity,
+
+    /// The proxy ownership has been renounced.
+    ProxyRevoked: (),
 }
\ No newline at end of file
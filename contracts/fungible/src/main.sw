@@ -5,12 +5,12 @@ mod errors;
 
 use std::{
     asset::*,
-    context::{this_balance, balance_of},
+    context::{balance_of, msg_amount},
     hash::{
         Hash,
         sha256,
     },
-    revert::require,
+    revert::{require, revert_with_log},
     storage::storage_string::*,
     string::String,
     contract_id::ContractId,
@@ -22,41 +22,51 @@ use std::{
 };
 use src20::SRC20;
 use src3::SRC3;
-use fungible_abi::*;
+use standards::src5::State;
+use standards::src20::{SetDecimalsEvent, SetNameEvent, SetSymbolEvent, TotalSupplyEvent};
+use interfaces::fungible_abi::*;
 use errors::*;
 
-/// Represents the state of proxy ownership
-enum State {
-    //
-    // The proxy has not been initialized yet
-    Uninitialized: (),
-    /// The proxy has been initialized with an owner
-    Initialized: Identity,
-    /// The proxy has been revoked
-    Revoked: (),
+configurable {
+    /// The max supply recorded for each asset when it is first minted.
+    MAX_SUPPLY: u64 = 1_000_000_000_000_000_000u64,
+    /// The proxy owner, fixed at deployment so an uninitialized contract can't be claimed by a stranger.
+    INITIAL_PROXY_OWNER: State = State::Uninitialized,
 }
 
+// Contract state is namespaced so it can never collide with the SRC14 slots
+// of the proxy the implementation runs behind.
 storage {
-    /// The name associated with a particular asset.
-    name: StorageMap<AssetId, StorageString> = StorageMap {},
-    /// The symbol associated with a particular asset.
-    symbol: StorageMap<AssetId, StorageString> = StorageMap {},
-    /// The decimals associated with a particular asset.
-    decimals: StorageMap<AssetId, u8> = StorageMap {},
-    /// The total number of coins minted for a particular asset.
-    total_supply: StorageMap<AssetId, u64> = StorageMap {},
-    /// The total number of unique assets minted by this contract.
-    total_assets: u64 = 0,
-    /// The owner of the contract.
-    owner: Option<Identity> = Option::None,
+    fungible {
+        /// The name associated with a particular asset.
+        name: StorageMap<AssetId, StorageString> = StorageMap {},
+        /// The symbol associated with a particular asset.
+        symbol: StorageMap<AssetId, StorageString> = StorageMap {},
+        /// The decimals associated with a particular asset.
+        decimals: StorageMap<AssetId, u8> = StorageMap {},
+        /// The total number of coins minted for a particular asset.
+        total_supply: StorageMap<AssetId, u64> = StorageMap {},
+        /// The max supply of a particular asset, fixed when it is first minted.
+        max_supply: StorageMap<AssetId, u64> = StorageMap {},
+        /// The total number of unique assets minted by this contract.
+        total_assets: u64 = 0,
+        /// The owner of the contract.
+        owner: Option<Identity> = Option::None,
+        /// The approved strategy contracts that can mint/burn
+        approved_strategies: StorageMap<ContractId, bool> = StorageMap {},
+        /// The strategy each sub-identifier is reserved for.
+        sub_id_strategies: StorageMap<SubId, ContractId> = StorageMap {},
+        /// The optional cap on a strategy's outstanding supply across its sub-identifiers.
+        strategy_supply_caps: StorageMap<ContractId, u64> = StorageMap {},
+        /// The outstanding supply minted by a strategy across its sub-identifiers.
+        strategy_supply: StorageMap<ContractId, u64> = StorageMap {},
+        /// The approved strategy that first minted a particular asset.
+        minters: StorageMap<AssetId, ContractId> = StorageMap {},
+    },
     /// Proxy implementation target
-    target: Option<ContractId> = None,
+    target in 0x7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55: Option<ContractId> = None,
     /// Proxy ownership state
-    proxy_owner: State = State::Uninitialized,
-    /// Flag to track if constructor has been called
-    constructor_called: bool = false,
-    /// The approved strategy contracts that can mint/burn
-    approved_strategies: StorageMap<ContractId, bool> = StorageMap {},
+    proxy_owner in 0xbb79927b15d9259ea316f2ecb2297d6cc8851888a98278c0a2e03e1a091ea754: State = State::Uninitialized,
 }
 
 /// Event emitted when the owner is updated
@@ -77,38 +87,68 @@ struct StrategyUpdated {
     approved: bool,
 }
 
+/// Event emitted when a sub-identifier is reserved for a strategy
+struct SubIdAssigned {
+    strategy: ContractId,
+    sub_id: SubId,
+}
+
+/// Event emitted when a strategy's supply cap is changed
+struct StrategySupplyCapUpdated {
+    strategy: ContractId,
+    cap: Option<u64>,
+}
+
+/// Event emitted when contract-held coins are transferred out
+struct Transferred {
+    sub_id: SubId,
+    amount: u64,
+    to: Identity,
+    sender: Identity,
+}
+
 #[storage(read)]
 fn only_owner() {
-    let owner_opt = storage.owner.read();
+    let owner_opt = storage::fungible.owner.read();
     require(
         owner_opt.is_some() && msg_sender().unwrap() == owner_opt.unwrap(),
         Error::Unauthorized(msg_sender().unwrap())
     );
 }
 
+/// Returns the stored proxy owner, falling back to the one configured at
+/// deployment until `initialize` has persisted it.
+#[storage(read)]
+fn proxy_owner() -> State {
+    match storage.proxy_owner.read() {
+        State::Uninitialized => INITIAL_PROXY_OWNER,
+        owner_state => owner_state,
+    }
+}
+
 #[storage(read)]
 fn only_proxy_owner() {
-    let proxy_state = storage.proxy_owner.read();
+    let owner_state = proxy_owner();
 
-    match proxy_state {
-        State::Uninitialized => {
-            // Allow the call if uninitialized
-            return;
-        },
-       State::Initialized(owner) => {
-            require(msg_sender().unwrap() == owner, Error::NotOwner);
-        },
+    match owner_state {
         State::Revoked => {
-            revert(0);
+            revert_with_log(Error::ProxyRevoked);
+        },
+        _ => {
+            // An uninitialized owner matches nobody
+            require(
+                owner_state == State::Initialized(msg_sender().unwrap()),
+                Error::NotProxyOwner(msg_sender().unwrap()),
+            );
         }
-    } 
+    }
 }
 
 
 impl UpgradableAsset for Contract {
     #[storage(read, write)]
     fn initialize_owner() -> Identity {
-        let owner = storage.owner.read();
+        let owner = storage::fungible.owner.read();
 
         // Make sure the owner has NOT already been initialized
         require(owner.is_none(), Error::OwnerAlreadyInitialized);
@@ -116,7 +156,7 @@ impl UpgradableAsset for Contract {
         // Get the identity of the sender        
         let sender = msg_sender().unwrap();
         // Set the owner to the sender's identity
-        storage.owner.write(Option::Some(sender));
+        storage::fungible.owner.write(Option::Some(sender));
 
         log(OwnerUpdated {
             old_owner: Identity::Address(Address::from(ZERO_B256)),
@@ -130,17 +170,17 @@ impl UpgradableAsset for Contract {
     #[storage(read)]
     fn get_owner() -> Identity {
         require(
-            storage.owner.read().is_some(),
+            storage::fungible.owner.read().is_some(),
             Error::OwnerNotInitialized
         );
-        storage.owner.read().unwrap()
+        storage::fungible.owner.read().unwrap()
     }
 
     #[storage(read, write)]
     fn set_owner(new_owner: Identity) {
         only_owner();
-        let old_owner = storage.owner.read().unwrap();
-        storage.owner.write(Option::Some(new_owner));
+        let old_owner = storage::fungible.owner.read().unwrap();
+        storage::fungible.owner.write(Option::Some(new_owner));
 
         log(OwnerUpdated {
             old_owner: old_owner,
@@ -150,18 +190,20 @@ impl UpgradableAsset for Contract {
     
     #[storage(read, write)]
     fn initialize(initial_target: ContractId) {
+        // Only the owner configured at deployment can initialize
+        only_proxy_owner();
+
         // Check if already initialized
         let current_state = storage.proxy_owner.read();
         match current_state {
             State::Uninitialized => {
-                // Set the initial owner
-                let sender = msg_sender().unwrap();
-                storage.proxy_owner.write(State::Initialized(sender));
+                // Persist the configured owner
+                storage.proxy_owner.write(INITIAL_PROXY_OWNER);
                 // Set the initial target
                 storage.target.write(Some(initial_target));
             },
             _ => {
-                revert(1);
+                revert_with_log(Error::AlreadyInitialized);
             }
         };
     }
@@ -198,7 +240,7 @@ impl UpgradableAsset for Contract {
             Error::AddressZero
         );
         
-        storage.approved_strategies.insert(strategy, approved);
+        storage::fungible.approved_strategies.insert(strategy, approved);
         
         log(StrategyUpdated {
             strategy: strategy,
@@ -208,14 +250,68 @@ impl UpgradableAsset for Contract {
     
     #[storage(read)]
     fn is_strategy_approved(strategy: ContractId) -> bool {
-        storage.approved_strategies.get(strategy).try_read().unwrap_or(false)
+        storage::fungible.approved_strategies.get(strategy).try_read().unwrap_or(false)
+    }
+
+    #[storage(read, write)]
+    fn assign_sub_id(strategy: ContractId, sub_id: SubId) {
+        only_owner();
+
+        require(
+            strategy != ContractId::from(ZERO_B256),
+            Error::AddressZero
+        );
+
+        // A sub-identifier can never move to another strategy once reserved
+        if let Some(assigned) = storage::fungible.sub_id_strategies.get(sub_id).try_read() {
+            require(assigned == strategy, Error::SubIdAlreadyAssigned(assigned));
+        }
+
+        storage::fungible.sub_id_strategies.insert(sub_id, strategy);
+
+        log(SubIdAssigned {
+            strategy: strategy,
+            sub_id: sub_id,
+        });
+    }
+
+    #[storage(read)]
+    fn sub_id_strategy(sub_id: SubId) -> Option<ContractId> {
+        storage::fungible.sub_id_strategies.get(sub_id).try_read()
+    }
+
+    #[storage(read, write)]
+    fn set_strategy_supply_cap(strategy: ContractId, cap: Option<u64>) {
+        only_owner();
+
+        match cap {
+            Some(cap) => storage::fungible.strategy_supply_caps.insert(strategy, cap),
+            None => {
+                let _ = storage::fungible.strategy_supply_caps.remove(strategy);
+            },
+        };
+
+        log(StrategySupplyCapUpdated {
+            strategy: strategy,
+            cap: cap,
+        });
+    }
+
+    #[storage(read)]
+    fn strategy_supply_cap(strategy: ContractId) -> Option<u64> {
+        storage::fungible.strategy_supply_caps.get(strategy).try_read()
+    }
+
+    #[storage(read)]
+    fn strategy_supply(strategy: ContractId) -> u64 {
+        storage::fungible.strategy_supply.get(strategy).try_read().unwrap_or(0)
     }
 }
 
 /// Add this helper function
 #[storage(read)]
 fn only_owner_or_strategy() {
-    let owner_opt = storage.owner.read();
+    let owner_opt = storage::fungible.owner.read();
     let sender_identity = msg_sender().unwrap();
     
     // Check if it's the owner
@@ -224,17 +320,56 @@ fn only_owner_or_strategy() {
     }
     
     // Check if it's an approved strategy
-    if let Identity::ContractId(contract_id) = sender_identity {
-        require(
-            storage.approved_strategies.get(contract_id).try_read().unwrap_or(false),
-            Error::Unauthorized(sender_identity)
-        );
-    } else {
-        // Neither owner nor approved strategy
-        revert(1);
+    let is_strategy = match sender_identity {
+        Identity::ContractId(contract_id) => {
+            storage::fungible.approved_strategies.get(contract_id).try_read().unwrap_or(false)
+        },
+        _ => false,
+    };
+    require(is_strategy, Error::Unauthorized(sender_identity));
+}
+
+/// Restricts minting and burning of `sub_id` to the owner or the approved strategy it is
+/// reserved for. Returns the strategy when the caller is not the owner.
+#[storage(read)]
+fn only_owner_or_assigned_strategy(sub_id: SubId) -> Option<ContractId> {
+    only_owner_or_strategy();
+
+    let sender_identity = msg_sender().unwrap();
+    let owner_opt = storage::fungible.owner.read();
+    if owner_opt.is_some() && sender_identity == owner_opt.unwrap() {
+        return None;
     }
+
+    // Past `only_owner_or_strategy`, any other caller is an approved strategy contract
+    let strategy = sender_identity.as_contract_id().unwrap();
+    let assigned = match storage::fungible.sub_id_strategies.get(sub_id).try_read() {
+        Some(assigned) => assigned == strategy,
+        None => false,
+    };
+    require(assigned, Error::SubIdNotAssigned(sub_id));
+
+    Some(strategy)
 }
 
+/// Restricts asset metadata to the owner or the approved strategy that minted the asset.
+#[storage(read)]
+fn only_owner_or_minter(asset_id: AssetId) {
+    let owner_opt = storage::fungible.owner.read();
+    let sender_identity = msg_sender().unwrap();
+
+    if owner_opt.is_some() && sender_identity == owner_opt.unwrap() {
+        return;
+    }
+
+    let is_minter = match (sender_identity, storage::fungible.minters.get(asset_id).try_read()) {
+        (Identity::ContractId(contract_id), Some(minter)) => {
+            contract_id == minter && storage::fungible.approved_strategies.get(contract_id).try_read().unwrap_or(false)
+        },
+        _ => false,
+    };
+    require(is_minter, Error::Unauthorized(sender_identity));
+}
 
 impl FungibleAsset for Contract {
     /*
@@ -246,27 +381,32 @@ impl FungibleAsset for Contract {
     */
     #[storage(read)]
     fn total_assets() -> u64 {
-        storage.total_assets.try_read().unwrap_or(0)
+        storage::fungible.total_assets.try_read().unwrap_or(0)
     }
 
     #[storage(read)]
     fn total_supply(asset_id: AssetId) -> Option<u64> {
-        storage.total_supply.get(asset_id).try_read()
+        storage::fungible.total_supply.get(asset_id).try_read()
     }
 
     #[storage(read)]
     fn name(asset_id: AssetId) -> Option<String> {
-        storage.name.get(asset_id).read_slice()
+        storage::fungible.name.get(asset_id).read_slice()
     }
 
     #[storage(read)]
     fn symbol(asset_id: AssetId) -> Option<String> {
-        storage.symbol.get(asset_id).read_slice()
+        storage::fungible.symbol.get(asset_id).read_slice()
     }
 
     #[storage(read)]
     fn decimals(asset_id: AssetId) -> Option<u8> {
-        storage.decimals.get(asset_id).try_read()
+        storage::fungible.decimals.get(asset_id).try_read()
+    }
+
+    #[storage(read)]
+    fn max_supply(asset_id: AssetId) -> Option<u64> {
+        storage::fungible.max_supply.get(asset_id).try_read()
     }
 
     /*
@@ -279,46 +419,79 @@ impl FungibleAsset for Contract {
     #[storage(read, write)]
     fn mint(recipient: Identity, sub_id: SubId, amount: u64) {
 
-         // Allow both owner and approved strategies
-        only_owner_or_strategy();
+         // Allow the owner and the strategy the sub-identifier is reserved for
+        let strategy = only_owner_or_assigned_strategy(sub_id);
+
+        if let Some(strategy) = strategy {
+            let strategy_supply = storage::fungible.strategy_supply.get(strategy).try_read().unwrap_or(0) + amount;
+            if let Some(cap) = storage::fungible.strategy_supply_caps.get(strategy).try_read() {
+                require(strategy_supply <= cap, Error::StrategySupplyCapExceeded(cap));
+            }
+            storage::fungible.strategy_supply.insert(strategy, strategy_supply);
+        }
 
         let asset_id = AssetId::new(ContractId::this(), sub_id);
 
-        let supply = storage.total_supply.get(asset_id);
+        let supply = storage::fungible.total_supply.get(asset_id);
 
         // Only increment the number of assets minted by this contract if it hasn't been minted before.
         if supply.try_read().is_none() {
-            storage.total_assets.write(storage.total_assets.read() + 1);
+            storage::fungible.total_assets.write(storage::fungible.total_assets.read() + 1);
+            storage::fungible.max_supply.insert(asset_id, MAX_SUPPLY);
+
+            // Remember which strategy owns the asset's metadata
+            if let Identity::ContractId(contract_id) = msg_sender().unwrap() {
+                storage::fungible.minters.insert(asset_id, contract_id);
+            }
         }
 
-        storage
-            .total_supply
-            .insert(asset_id, supply.try_read().unwrap_or(0) + amount);
+        let max_supply = storage::fungible.max_supply.get(asset_id).read();
+        let new_supply = supply.try_read().unwrap_or(0) + amount;
+        require(new_supply <= max_supply, Error::MaxSupplyReached(max_supply));
+        storage::fungible.total_supply.insert(asset_id, new_supply);
 
         // The `asset_id` constructed within the `mint_to` method is a sha256 hash of
         // the `contract_id` and the `sub_id` (the same as the `asset_id` constructed here).
         mint_to(recipient, sub_id, amount);
+
+        TotalSupplyEvent::new(asset_id, new_supply, msg_sender().unwrap())
+            .log();
     }
 
-    #[storage(read, write)]
+    #[storage(read, write), payable]
     fn burn(sub_id: SubId, amount: u64) {
 
-         // Allow both owner and approved strategies
-        only_owner_or_strategy();
+         // Allow the owner and the strategy the sub-identifier is reserved for
+        let strategy = only_owner_or_assigned_strategy(sub_id);
 
         let asset_id = AssetId::new(ContractId::this(), sub_id);
 
+        // Only the coins forwarded with this call are burned
         require(
-            this_balance(asset_id) >= amount,
-            Error::BurnInsufficientBalance,
+            msg_amount() == amount,
+            Error::IncorrectAmountProvided(msg_amount()),
         );
+        require(
+            msg_asset_id() == asset_id,
+            Error::IncorrectAssetProvided(msg_asset_id()),
+        );
+        let supply = storage::fungible.total_supply.get(asset_id).try_read().unwrap_or(0);
+        require(amount <= supply, Error::InsufficientSupply(supply));
 
-        // If we pass the check above, we can assume it is safe to unwrap.
-        storage
-            .total_supply
-            .insert(asset_id, storage.total_supply.get(asset_id).read() - amount);
+        let new_supply = supply - amount;
+        storage::fungible.total_supply.insert(asset_id, new_supply);
+
+        // Coins minted by the owner can be burned by the strategy, so never underflow
+        if let Some(strategy) = strategy {
+            let strategy_supply = storage::fungible.strategy_supply.get(strategy).try_read().unwrap_or(0);
+            let remaining = if strategy_supply > amount { strategy_supply - amount } else { 0 };
+            storage::fungible.strategy_supply.insert(strategy, remaining);
+        }
 
         burn(sub_id, amount);
+
+        TotalSupplyEvent::new(asset_id, new_supply, msg_sender().unwrap())
+            .log();
     }
 
     /*
@@ -328,45 +501,54 @@ impl FungibleAsset for Contract {
         / / /    ___) |  __/ |_| ||  __/ |  \__ \
        /_/_/    |____/ \___|\__|\__\___|_|  |___/
     */
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_name(asset_id: AssetId, name: String) {
+        only_owner_or_minter(asset_id);
         require(
-            storage
+            storage::fungible
                 .name
                 .get(asset_id)
                 .read_slice()
                 .is_none(),
             Error::NameAlreadySet,
         );
-        storage.name.insert(asset_id, StorageString {});
-        storage.name.get(asset_id).write_slice(name);
+        storage::fungible.name.insert(asset_id, StorageString {});
+        storage::fungible.name.get(asset_id).write_slice(name);
+
+        SetNameEvent::new(asset_id, Some(name), msg_sender().unwrap()).log();
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_symbol(asset_id: AssetId, symbol: String) {
+        only_owner_or_minter(asset_id);
         require(
-            storage
+            storage::fungible
                 .symbol
                 .get(asset_id)
                 .read_slice()
                 .is_none(),
             Error::SymbolAlreadySet,
         );
-        storage.symbol.insert(asset_id, StorageString {});
-        storage.symbol.get(asset_id).write_slice(symbol);
+        storage::fungible.symbol.insert(asset_id, StorageString {});
+        storage::fungible.symbol.get(asset_id).write_slice(symbol);
+
+        SetSymbolEvent::new(asset_id, Some(symbol), msg_sender().unwrap()).log();
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_decimals(asset_id: AssetId, decimals: u8) {
+        only_owner_or_minter(asset_id);
         require(
-            storage
+            storage::fungible
                 .decimals
                 .get(asset_id)
                 .try_read()
                 .is_none(),
             Error::DecimalsAlreadySet,
         );
-        storage.decimals.insert(asset_id, decimals);
+        storage::fungible.decimals.insert(asset_id, decimals);
+
+        SetDecimalsEvent::new(asset_id, decimals, msg_sender().unwrap()).log();
     }
 
     /*
@@ -393,10 +575,23 @@ impl FungibleAsset for Contract {
         / / /     | || | | (_| | | | \__ \  _|  __/ |   
        /_/_/      |_||_|  \__,_|_| |_|___/_|  \___|_|
     */
+    #[storage(read)]
     fn transfer(to: Identity, sub_id: SubId, amount: u64) {
+        // Allow the owner and the strategy the sub-identifier is reserved for
+        let _ = only_owner_or_assigned_strategy(sub_id);
+
         let asset_id = AssetId::new(ContractId::this(), sub_id);
+        let balance = balance_of(ContractId::this(), asset_id);
+        require(amount <= balance, Error::InsufficientBalance(balance));
 
         transfer(to, asset_id, amount);
+
+        log(Transferred {
+            sub_id: sub_id,
+            amount: amount,
+            to: to,
+            sender: msg_sender().unwrap(),
+        });
     }
 }
 
@@ -424,8 +619,8 @@ fn test_mint() {
 }
 
 
-#[test]
-fn test_burn() {
+#[test(should_revert)]
+fn test_burn_requires_forwarded_coins() {
     use std::constants::ZERO_B256;
      // Initialize the contract's owner first
     let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
@@ -438,9 +633,8 @@ fn test_burn() {
     fungible_abi.mint(recipient, sub_id, 100);
     log(balance_of(ContractId::from(CONTRACT_ID), asset_id)); 
     assert(balance_of(ContractId::from(CONTRACT_ID), asset_id) == 100);
+    // Coins held by the contract itself can no longer be burned
     fungible_abi.burn(sub_id, 100);
-    log(balance_of(ContractId::from(CONTRACT_ID), asset_id)); 
-    assert(balance_of(ContractId::from(CONTRACT_ID), asset_id) == 0);
 }
 
 #[test]
@@ -481,6 +675,9 @@ fn test_total_supply() {
 #[test]
 fn test_name() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -494,6 +691,9 @@ fn test_name() {
 #[test(should_revert)]
 fn test_revert_set_name_twice() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -506,6 +706,9 @@ fn test_revert_set_name_twice() {
 #[test]
 fn test_symbol() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -519,6 +722,9 @@ fn test_symbol() {
 #[test(should_revert)]
 fn test_revert_set_symbol_twice() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -531,6 +737,9 @@ fn test_revert_set_symbol_twice() {
 #[test]
 fn test_decimals() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -544,6 +753,9 @@ fn test_decimals() {
 #[test(should_revert)]
 fn test_revert_set_decimals_twice() {
     use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
     let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
     let sub_id = ZERO_B256;
     let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
@@ -553,6 +765,47 @@ fn test_revert_set_decimals_twice() {
     fungible_abi.set_decimals(asset_id, decimals);
 }
 
+#[test(should_revert)]
+fn test_revert_set_name_without_owner() {
+    use std::constants::ZERO_B256;
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), ZERO_B256);
+
+    // Nobody may set metadata before an owner exists
+    fungible_abi.set_name(asset_id, String::from_ascii_str("Burra Labs Asset"));
+}
+
+#[test(should_revert)]
+fn test_revert_set_symbol_without_owner() {
+    use std::constants::ZERO_B256;
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), ZERO_B256);
+
+    fungible_abi.set_symbol(asset_id, String::from_ascii_str("BURRA"));
+}
+
+#[test(should_revert)]
+fn test_revert_set_decimals_without_owner() {
+    use std::constants::ZERO_B256;
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), ZERO_B256);
+
+    fungible_abi.set_decimals(asset_id, 8u8);
+}
+
+#[test(should_revert)]
+fn test_revert_set_name_after_owner_changed() {
+    use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), ZERO_B256);
+
+    // Hand ownership to someone else, the caller is now a stranger
+    upgradable_abi.initialize_owner();
+    upgradable_abi.set_owner(Identity::Address(Address::from(0x1111111111111111111111111111111111111111111111111111111111111111)));
+    fungible_abi.set_name(asset_id, String::from_ascii_str("Burra Labs Asset"));
+}
+
 #[test]
 fn test_initialize_owner() {
     let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
@@ -581,45 +834,21 @@ fn test_set_owner() {
     assert(upgradable_abi.get_owner() == new_owner);
 }
 
-#[test]
-fn test_initialize_proxy() {
+// No proxy owner is configured here, so nobody can claim the proxy
+#[test(should_revert)]
+fn test_revert_initialize_proxy_without_configured_owner() {
     let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
     let target = ContractId::from(0x4444444444444444444444444444444444444444444444444444444444444444);
     
-    // Initialize proxy
     upgradable_abi.initialize(target);
-    
-    // Verify implementation
-    assert(upgradable_abi.get_implementation().unwrap() == target);
 }
 
 #[test(should_revert)]
-fn test_revert_initialize_proxy_twice() {
+fn test_revert_set_implementation_without_configured_owner() {
     let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
-    let first_target = ContractId::from(0x4444444444444444444444444444444444444444444444444444444444444444);
-    let second_target = ContractId::from(0x5555555555555555555555555555555555555555555555555555555555555555);
-    
-    // First initialization works
-    upgradable_abi.initialize(first_target);
-    
-    // Second initialization should revert
-    upgradable_abi.initialize(second_target);
-}
-
-#[test]
-fn test_set_implementation() {
-    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
-    let initial_target = ContractId::from(0x6666666666666666666666666666666666666666666666666666666666666666);
     let new_target = ContractId::from(0x7777777777777777777777777777777777777777777777777777777777777777);
     
-    // Initialize proxy
-    upgradable_abi.initialize(initial_target);
-    
-    // Update implementation
     upgradable_abi.set_implementation(new_target);
-    
-    // Verify new implementation
-    assert(upgradable_abi.get_implementation().unwrap() == new_target);
 }
 
 #[test]
@@ -667,28 +896,107 @@ fn test_ownership_with_asset_metadata() {
 }
 
 #[test]
-fn test_integrated_ownership_and_proxy() {
+fn test_ownership_does_not_grant_proxy_ownership() {
     let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
     
     // Initialize owner
     let owner = upgradable_abi.initialize_owner();
-    
-    // Initialize proxy
-    let implementation = ContractId::from(0x1313131313131313131313131313131313131313131313131313131313131313);
-    upgradable_abi.initialize(implementation);
-    
-    // Verify both were set correctly
     assert(upgradable_abi.get_owner() == owner);
-    assert(upgradable_abi.get_implementation().unwrap() == implementation);
     
-    // Set new owner and implementation
+    // Hand ownership on, the proxy target is untouched
     let new_owner = Identity::Address(Address::from(0x1414141414141414141414141414141414141414141414141414141414141414));
     upgradable_abi.set_owner(new_owner);
     
-    let new_implementation = ContractId::from(0x1515151515151515151515151515151515151515151515151515151515151515);
-    upgradable_abi.set_implementation(new_implementation);
-    
-    // Verify both changes took effect
     assert(upgradable_abi.get_owner() == new_owner);
-    assert(upgradable_abi.get_implementation().unwrap() == new_implementation);
-}
\ No newline at end of file
+    assert(upgradable_abi.get_implementation().is_none());
+}
+#[test]
+fn test_assign_sub_id_and_supply_cap() {
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let strategy = ContractId::from(0x1616161616161616161616161616161616161616161616161616161616161616);
+    let sub_id = 0x0000000000000000000000000000000000000000000000000000000000000001;
+
+    assert(upgradable_abi.sub_id_strategy(sub_id).is_none());
+    upgradable_abi.assign_sub_id(strategy, sub_id);
+    assert(upgradable_abi.sub_id_strategy(sub_id).unwrap() == strategy);
+
+    assert(upgradable_abi.strategy_supply_cap(strategy).is_none());
+    upgradable_abi.set_strategy_supply_cap(strategy, Some(1000));
+    assert(upgradable_abi.strategy_supply_cap(strategy).unwrap() == 1000);
+    upgradable_abi.set_strategy_supply_cap(strategy, None);
+    assert(upgradable_abi.strategy_supply_cap(strategy).is_none());
+
+    // Owner mints don't count towards any strategy
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    fungible_abi.mint(Identity::ContractId(ContractId::from(CONTRACT_ID)), sub_id, 100);
+    assert(upgradable_abi.strategy_supply(strategy) == 0);
+}
+
+#[test(should_revert)]
+fn test_assign_sub_id_to_second_strategy() {
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let sub_id = 0x0000000000000000000000000000000000000000000000000000000000000001;
+    upgradable_abi.assign_sub_id(ContractId::from(0x1616161616161616161616161616161616161616161616161616161616161616), sub_id);
+    upgradable_abi.assign_sub_id(ContractId::from(0x1717171717171717171717171717171717171717171717171717171717171717), sub_id);
+}
+
+#[test]
+fn test_owner_transfers_contract_held_coins() {
+    use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let sub_id = ZERO_B256;
+    fungible_abi.mint(Identity::ContractId(ContractId::from(CONTRACT_ID)), sub_id, 100);
+    assert(fungible_abi.this_balance(sub_id) == 100);
+
+    let recipient = ContractId::from(0x1818181818181818181818181818181818181818181818181818181818181818);
+    fungible_abi.transfer(Identity::ContractId(recipient), sub_id, 40);
+    assert(fungible_abi.this_balance(sub_id) == 60);
+    assert(fungible_abi.get_balance(recipient, sub_id) == 40);
+}
+
+#[test(should_revert)]
+fn test_transfer_more_than_held() {
+    use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let sub_id = ZERO_B256;
+    fungible_abi.mint(Identity::ContractId(ContractId::from(CONTRACT_ID)), sub_id, 100);
+    fungible_abi.transfer(Identity::ContractId(ContractId::from(CONTRACT_ID)), sub_id, 101);
+}
+
+#[test]
+fn test_max_supply_set_at_first_mint() {
+    use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let recipient = Identity::ContractId(ContractId::from(CONTRACT_ID));
+    let sub_id = ZERO_B256;
+    let asset_id = AssetId::new(ContractId::from(CONTRACT_ID), sub_id);
+
+    assert(fungible_abi.max_supply(asset_id).is_none());
+    fungible_abi.mint(recipient, sub_id, 100);
+    assert(fungible_abi.max_supply(asset_id).unwrap() == MAX_SUPPLY);
+}
+
+#[test(should_revert)]
+fn test_mint_above_max_supply() {
+    use std::constants::ZERO_B256;
+    let upgradable_abi = abi(UpgradableAsset, CONTRACT_ID);
+    upgradable_abi.initialize_owner();
+
+    let fungible_abi = abi(FungibleAsset, CONTRACT_ID);
+    let recipient = Identity::ContractId(ContractId::from(CONTRACT_ID));
+    fungible_abi.mint(recipient, ZERO_B256, MAX_SUPPLY);
+    fungible_abi.mint(recipient, ZERO_B256, 1);
+}
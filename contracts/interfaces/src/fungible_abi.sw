@@ -1,4 +1,4 @@
-
+// SPDX-License-Identifier: Apache-2.0
 library;
 
 use std::{
@@ -43,9 +43,36 @@ abi UpgradableAsset {
     /// Checks if a strategy is approved
     #[storage(read)]
     fn is_strategy_approved(strategy: ContractId) -> bool;
+
+    /// Reserves a sub-identifier for a strategy, which may then only mint/burn its reserved sub-identifiers
+    #[storage(read, write)]
+    fn assign_sub_id(strategy: ContractId, sub_id: SubId);
+
+    /// Gets the strategy a sub-identifier is reserved for
+    #[storage(read)]
+    fn sub_id_strategy(sub_id: SubId) -> Option<ContractId>;
+
+    /// Sets or clears the cap on a strategy's outstanding supply across its sub-identifiers
+    #[storage(read, write)]
+    fn set_strategy_supply_cap(strategy: ContractId, cap: Option<u64>);
+
+    /// Gets a strategy's supply cap, if any
+    #[storage(read)]
+    fn strategy_supply_cap(strategy: ContractId) -> Option<u64>;
+
+    /// Gets the outstanding supply minted by a strategy
+    #[storage(read)]
+    fn strategy_supply(strategy: ContractId) -> u64;
 }
 
 abi FungibleAsset {
+    /*
+           ____  ____  ____   ____ ____   ___  
+          / / / / ___||  _ \ / ___|___ \ / _ \ 
+         / / /  \___ \| |_) | |     __) | | | |
+        / / /    ___) |  _ <| |___ / __/| |_| |
+       /_/_/    |____/|_| \_\\____|_____|\___/                                          
+    */
 
     #[storage(read)]
     fn total_assets() -> u64;
@@ -63,19 +90,128 @@ abi FungibleAsset {
     #[storage(read)]
     fn decimals(asset_id: AssetId) -> Option<u8>;
 
+    /// Returns the max supply of an asset, fixed when it is first minted.
+    #[storage(read)]
+    fn max_supply(asset_id: AssetId) -> Option<u64>;
+
+    /*
+           ____  ____  ____   ____ _____ 
+          / / / / ___||  _ \ / ___|___ / 
+         / / /  \___ \| |_) | |     |_ \ 
+        / / /    ___) |  _ <| |___ ___) |
+       /_/_/    |____/|_| \_\\____|____/   
+       
+    */
+    /// Mints new assets using the `vault_sub_id` sub-identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient`: [Identity] - The user to which the newly minted asset is transferred to.
+    /// * `vault_sub_id`: [SubId] - The sub-identifier of the newly minted asset.
+    /// * `amount`: [u64] - The quantity of coins to mint.
+    ///
+    /// # Examples
+    ///
+    /// ```sway
+    /// use src3::SRC3;
+    ///
+    /// fn foo(contract_id: ContractId) {
+    ///     let contract_abi = abi(SR3, contract);
+    ///     contract_abi.mint(Identity::ContractId(contract_id), ZERO_B256, 100);
+    /// }
+    /// ```
     #[storage(read, write)]
     fn mint(recipient: Identity, vault_sub_id: SubId, amount: u64);
 
-    #[storage(read, write)]
+    /// Burns assets sent with the given `vault_sub_id`.
+    ///
+    /// # Additional Information
+    ///
+    /// Only the coins forwarded with the call are burned; the forwarded asset and amount must match
+    /// the asset of `vault_sub_id` and `amount`.
+    ///
+    /// NOTE: The sha-256 hash of `(ContractId, SubId)` must match the `AssetId` where `ContractId` is the id of
+    /// the implementing contract and `SubId` is the given `vault_sub_id` argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `vault_sub_id`: [SubId] - The sub-identifier of the asset to burn.
+    /// * `amount`: [u64] - The quantity of coins to burn.
+    ///
+    /// # Examples
+    ///
+    /// ```sway
+    /// use src3::SRC3;
+    ///
+    /// fn foo(contract_id: ContractId, asset_id: AssetId) {
+    ///     let contract_abi = abi(SR3, contract_id);
+    ///     contract_abi {
+    ///         gas: 10000,
+    ///         coins: 100,
+    ///         asset_id: asset_id,
+    ///     }.burn(ZERO_B256, 100);
+    /// }
+    /// ```
+    #[storage(read, write), payable]
     fn burn(vault_sub_id: SubId, amount: u64);
+
+    /*
+           ____  ____       _   _                
+          / / / / ___|  ___| |_| |_ ___ _ __ ___ 
+         / / /  \___ \ / _ \ __| __/ _ \ '__/ __|
+        / / /    ___) |  __/ |_| ||  __/ |  \__ \
+       /_/_/    |____/ \___|\__|\__\___|_|  |___/
+    */
     
-    #[storage(write)]
+    /// Sets the name of an asset, once. Only the owner or the approved
+    /// strategy that minted the asset may call this.
+    #[storage(read, write)]
     fn set_name(asset_id: AssetId, name: String);
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_symbol(asset_id: AssetId, symbol: String);
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_decimals(asset_id: AssetId, decimals: u8);
 
+    /*
+           ____  ____        _                      
+          / / / | __ )  __ _| | __ _ _ __   ___ ___ 
+         / / /  |  _ \ / _` | |/ _` | '_ \ / __/ _ \
+        / / /   | |_) | (_| | | (_| | | | | (_|  __/
+       /_/_/    |____/ \__,_|_|\__,_|_| |_|\___\___|
+    */
+
+    /// Returns how many coins of the `sub_id` asset this contract holds.
+    fn this_balance(sub_id: SubId) -> u64;
+
+    /// Returns how many coins of the `sub_id` asset the `target` contract holds.
+    fn get_balance(target: ContractId, sub_id: SubId) -> u64;
+
+    /*
+           ____  _____                     __           
+          / / / |_   _| __ __ _ _ __  ___ / _| ___ _ __ 
+         / / /    | || '__/ _` | '_ \/ __| |_ / _ \ '__|
+        / / /     | || | | (_| | | | \__ \  _|  __/ |   
+       /_/_/      |_||_|  \__,_|_| |_|___/_|  \___|_|
+    */
+
+    /// Transfers coins of the `sub_id` asset held by this contract to `to`.
+    ///
+    /// # Additional Information
+    ///
+    /// Only the owner or the approved strategy the `sub_id` is reserved for may call this.
+    ///
+    /// # Arguments
+    ///
+    /// * `to`: [Identity] - The recipient of the coins.
+    /// * `sub_id`: [SubId] - The sub-identifier of the asset to transfer.
+    /// * `amount`: [u64] - The quantity of coins to transfer.
+    ///
+    /// # Reverts
+    ///
+    /// * When the caller is neither the owner nor the strategy the `sub_id` is reserved for.
+    /// * When the contract holds fewer than `amount` coins of the asset.
+    #[storage(read)]
+    fn transfer(to: Identity, sub_id: SubId, amount: u64);
 }
@@ -3,3 +3,5 @@ library;
 pub mod sprout_token;
 pub mod mira_amm;
 pub mod fungible_abi;
+pub mod limit_order;
+pub mod strategy_vault;
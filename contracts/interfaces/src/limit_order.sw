@@ -0,0 +1,30 @@
+library;
+
+/// NAV per share is expressed in base asset units per share, scaled by this.
+pub const NAV_SCALE: u64 = 1_000_000_000;
+
+/// An order to enter or exit a strategy vault once its NAV per share crosses a limit.
+///
+/// The order script takes it as its only argument and the limit order predicate
+/// checks it against its own configuration before releasing funds.
+pub struct LimitOrder {
+    pub strategy: ContractId,
+    pub vault_id: SubId,
+    /// The vault's receipt asset, used to price shares and to exit.
+    pub share_asset: AssetId,
+    /// Who receives the minted shares, or the base asset on exit.
+    pub receiver: Identity,
+    /// Base asset to deposit, or shares to redeem on exit. The predicate
+    /// only releases fills of at least its minimum fill size.
+    pub amount: u64,
+    /// Entries fill at or below this NAV per share, exits at or above it.
+    pub nav_limit: u64,
+    pub exit: bool,
+}
+
+pub enum LimitOrderError {
+    /// The vault's NAV per share is above an entry order's limit.
+    NavAboveLimit: u64,
+    /// The vault's NAV per share is below an exit order's limit.
+    NavBelowLimit: u64,
+}
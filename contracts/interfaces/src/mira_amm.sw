@@ -4,7 +4,23 @@ use std::{bytes::Bytes, string::String};
 
 pub type PoolId = (AssetId, AssetId, bool);
 
+pub struct Asset {
+    pub id: AssetId,
+    pub amount: u64,
+}
+
+pub struct PoolMetadata {
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+    pub liquidity: Asset,
+    pub decimals_0: u8,
+    pub decimals_1: u8,
+}
+
 abi MiraAMM {
+    #[storage(read)]
+    fn pool_metadata(pool_id: PoolId) -> Option<PoolMetadata>;
+
     #[payable]
     #[storage(read, write)]
     fn swap(
@@ -1,14 +1,19 @@
 library;
 
+use std::string::String;
 
+// `total_assets`, `total_supply`, `name`, `symbol` and `decimals` match SRC20,
+// so a Sprout token can be read as an SRC20 asset contract.
 abi Sprout_Token {
     #[storage(read)]
     fn main_contract() -> ContractId;
 
+    /// Mints receipts. Only the main contract may call this.
     #[storage(read, write)]
     fn mint(user: Identity, sub_id: SubId, amount: u64);
 
-    #[storage(read, write)]
+    /// Burns the receipts forwarded with the call. Only the main contract may call this.
+    #[storage(read, write), payable]
     fn burn(sub_id: SubId, amount: u64);
 
     #[storage(read, write)]
@@ -28,5 +33,13 @@ abi Sprout_Token {
 
     #[storage(read)]
     fn total_supply(asset: AssetId) -> Option<u64>;
-}
 
+    #[storage(read)]
+    fn name(asset: AssetId) -> Option<String>;
+
+    #[storage(read)]
+    fn symbol(asset: AssetId) -> Option<String>;
+
+    #[storage(read)]
+    fn decimals(asset: AssetId) -> Option<u8>;
+}
@@ -0,0 +1,8 @@
+library;
+
+// The part of the strategy ABI other contracts need to find a vault's receipt
+// asset, which depends on the vault and on the strategy's receipt token mode.
+abi StrategyVault {
+    #[storage(read)]
+    fn asset_id(vault_id: SubId) -> AssetId;
+}
@@ -0,0 +1,37 @@
+script;
+
+use interfaces::limit_order::{LimitOrder, LimitOrderError, NAV_SCALE};
+use standards::{src6::SRC6, src20::SRC20};
+use std::convert::TryFrom;
+
+/// Current NAV per share of a vault, scaled by `NAV_SCALE`. An empty vault is priced at par.
+fn nav_per_share(order: LimitOrder) -> u64 {
+    let supply = abi(SRC20, order.strategy.into()).total_supply(order.share_asset).unwrap_or(0);
+    if supply == 0 {
+        return NAV_SCALE;
+    }
+
+    let managed = abi(SRC6, order.strategy.into()).managed_assets(AssetId::base(), order.vault_id);
+    u64::try_from(managed.as_u256() * NAV_SCALE.as_u256() / supply.as_u256()).unwrap()
+}
+
+/// Fills a limit order against its strategy, returning the shares minted on
+/// entry or the base asset paid out on exit.
+fn main(order: LimitOrder) -> u64 {
+    let nav = nav_per_share(order);
+    let strategy = abi(SRC6, order.strategy.into());
+
+    if order.exit {
+        require(nav >= order.nav_limit, LimitOrderError::NavBelowLimit(nav));
+        strategy {
+            asset_id: order.share_asset.into(),
+            coins: order.amount,
+        }.withdraw(order.receiver, AssetId::base(), order.vault_id)
+    } else {
+        require(nav <= order.nav_limit, LimitOrderError::NavAboveLimit(nav));
+        strategy {
+            asset_id: AssetId::base().into(),
+            coins: order.amount,
+        }.deposit(order.receiver, order.vault_id)
+    }
+}
@@ -0,0 +1,128 @@
+predicate;
+
+use interfaces::limit_order::{LimitOrder, NAV_SCALE};
+use std::{
+    auth::predicate_address,
+    codec::decode_script_data,
+    convert::TryFrom,
+    inputs::{
+        input_coin_owner,
+        input_count,
+    },
+    outputs::{
+        output_asset_to,
+        output_count,
+        output_type,
+        Output,
+    },
+    tx::{
+        tx_max_fee,
+        tx_script_bytecode_hash,
+    },
+};
+
+configurable {
+    // Receives the fill and can cancel the order at any time
+    OWNER: Address = Address::from(0x0000000000000000000000000000000000000000000000000000000000000000),
+    STRATEGY: ContractId = ContractId::from(0x0000000000000000000000000000000000000000000000000000000000000000),
+    VAULT_ID: SubId = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    SHARE_ASSET: AssetId = AssetId::from(0x0000000000000000000000000000000000000000000000000000000000000000),
+    // NAV per share scaled by `NAV_SCALE`: entries fill at or below it, exits at or above it
+    NAV_LIMIT: u64 = NAV_SCALE,
+    // Locks the vault's shares to exit instead of the base asset to enter
+    EXIT: bool = false,
+    // sha256 of the limit order script bytecode, the only script allowed to spend the funds
+    ORDER_SCRIPT_HASH: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // Caps what a filler can make the whole order pay in fees
+    MAX_FEE: u64 = 1_000_000,
+    // The size the order was placed with, each fill may spend its share of `MAX_FEE`
+    ORDER_AMOUNT: u64 = 0,
+    // Smallest amount a fill may take, so the order can't be split into dust
+    // fills. A remainder below it is left for the owner to cancel.
+    MIN_FILL: u64 = 1,
+}
+
+/// The owner spending one of their own coins alongside is a cancellation.
+fn signed_by_owner() -> bool {
+    let mut i = 0;
+    while i < input_count().as_u64() {
+        if let Some(owner) = input_coin_owner(i) {
+            if owner == OWNER {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Coins, including change, may only go back to the owner or to the predicate.
+fn outputs_stay_with_owner() -> bool {
+    let this = predicate_address().unwrap();
+    let mut i = 0;
+    while i < output_count().as_u64() {
+        let pays_out = match output_type(i) {
+            Some(Output::Coin) => true,
+            Some(Output::Change) => true,
+            _ => false,
+        };
+        if pays_out {
+            let to = output_asset_to(i).unwrap();
+            if to != OWNER && to != this {
+                return false;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+fn is_order_script() -> bool {
+    match tx_script_bytecode_hash() {
+        Some(hash) => hash == ORDER_SCRIPT_HASH,
+        None => false,
+    }
+}
+
+/// The part of `MAX_FEE` a fill of `amount` may spend, so the fees of all the
+/// fills together stay within it.
+fn fee_budget(amount: u64) -> u64 {
+    if ORDER_AMOUNT == 0 {
+        return 0;
+    }
+    let amount = if amount > ORDER_AMOUNT { ORDER_AMOUNT } else { amount };
+    u64::try_from(MAX_FEE.as_u256() * amount.as_u256() / ORDER_AMOUNT.as_u256()).unwrap()
+}
+
+/// The script's order must be exactly this predicate's order, except for the
+/// amount, so it can be filled in parts.
+fn is_this_order(order: LimitOrder) -> bool {
+    order.strategy == STRATEGY
+        && order.vault_id == VAULT_ID
+        && order.share_asset == SHARE_ASSET
+        && order.receiver == Identity::Address(OWNER)
+        && order.nav_limit == NAV_LIMIT
+        && order.exit == EXIT
+}
+
+fn main() -> bool {
+    if signed_by_owner() {
+        return true;
+    }
+
+    // The NAV check itself happens in the order script, which is why only it may spend
+    if !is_order_script() {
+        return false;
+    }
+    let order = decode_script_data::<LimitOrder>();
+
+    let fee_capped = match tx_max_fee() {
+        Some(max_fee) => max_fee <= fee_budget(order.amount),
+        None => false,
+    };
+
+    is_this_order(order)
+        && order.amount >= MIN_FILL
+        && fee_capped
+        && outputs_stay_with_owner()
+}
@@ -1,14 +1,35 @@
 contract;
 
 use std::{
+    asset::transfer,
     bytes::Bytes,
+    context::this_balance,
     identity::Identity,
     storage::*,
 };
 
 pub type PoolId = (AssetId, AssetId, bool);
 
+pub enum MockError {
+    InsufficientLiquidity: AssetId,
+}
+
+pub struct Asset {
+    pub id: AssetId,
+    pub amount: u64,
+}
+
+pub struct PoolMetadata {
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+    pub liquidity: Asset,
+    pub decimals_0: u8,
+    pub decimals_1: u8,
+}
+
 abi MiraAMM {
+    fn pool_metadata(p_id: PoolId) -> Option<PoolMetadata>;
+
     #[payable]
     fn swap(
         p_id: PoolId,
@@ -20,15 +41,38 @@ abi MiraAMM {
 }
 
 impl MiraAMM for Contract {
+    // Every pool shares the mock's balances as its reserves
+    fn pool_metadata(p_id: PoolId) -> Option<PoolMetadata> {
+        Some(PoolMetadata {
+            reserve_0: this_balance(p_id.0),
+            reserve_1: this_balance(p_id.1),
+            liquidity: Asset {
+                id: AssetId::zero(),
+                amount: 0,
+            },
+            decimals_0: 9,
+            decimals_1: 9,
+        })
+    }
+
     #[payable]
     fn swap(
-        _p_id: PoolId,
-        _amount_0_out: u64,
-        _amount_1_out: u64,
-        _to: Identity,
+        p_id: PoolId,
+        amount_0_out: u64,
+        amount_1_out: u64,
+        to: Identity,
         _data: Bytes,
     ) {
-        // Do nothing - just pretend it worked
-        // This is the simplest possible mock for testing
+        // Like a real pool, a swap the mock can't pay out fails. Tests seed
+        // the mock with the liquidity their swaps need.
+        require(this_balance(p_id.0) >= amount_0_out, MockError::InsufficientLiquidity(p_id.0));
+        require(this_balance(p_id.1) >= amount_1_out, MockError::InsufficientLiquidity(p_id.1));
+
+        if amount_0_out > 0 {
+            transfer(to, p_id.0, amount_0_out);
+        }
+        if amount_1_out > 0 {
+            transfer(to, p_id.1, amount_1_out);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,163 @@
+contract;
+
+use std::{execution::run_external, revert::revert_with_log};
+use standards::src5::{AccessError, State};
+
+/// The implementation and owner this proxy is deployed with.
+configurable {
+    INITIAL_TARGET: Option<ContractId> = None,
+    INITIAL_OWNER: State = State::Uninitialized,
+}
+
+pub struct Upgraded {
+    pub old_target: Option<ContractId>,
+    pub new_target: ContractId,
+}
+
+/// SRC14 event logged whenever the proxy target changes.
+pub struct ProxyTargetSet {
+    pub new_target: ContractId,
+}
+
+/// SRC14 event logged whenever the proxy owner changes.
+pub struct ProxyOwnerSet {
+    pub new_proxy_owner: State,
+}
+
+pub enum ProxyError {
+    AlreadyInitialized: (),
+    NotInitialized: (),
+    ProxyRevoked: (),
+}
+
+abi SRC14 {
+    #[storage(read, write)]
+    fn _set_proxy_target(new_target: ContractId);
+
+    #[storage(read)]
+    fn _proxy_target() -> Option<ContractId>;
+}
+
+abi SRC14Extension {
+    #[storage(read)]
+    fn _proxy_owner() -> State;
+
+    #[storage(read, write)]
+    fn _set_proxy_owner(new_proxy_owner: State);
+
+    #[storage(read, write)]
+    fn renounce_upgradability();
+}
+
+abi Proxy {
+    #[storage(read, write)]
+    fn initialize_proxy();
+}
+
+// Only the SRC14 slots live in the proxy's own storage. Implementations keep
+// their state in namespaced storage so it can never collide with these.
+storage {
+    target in 0x7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55: Option<ContractId> = None,
+    proxy_owner in 0xbb79927b15d9259ea316f2ecb2297d6cc8851888a98278c0a2e03e1a091ea754: State = State::Uninitialized,
+}
+
+#[storage(read)]
+fn only_proxy_owner() {
+    let owner_state = storage.proxy_owner.read();
+    if owner_state == State::Revoked {
+        revert_with_log(ProxyError::ProxyRevoked);
+    }
+
+    require(
+        owner_state == State::Initialized(msg_sender().unwrap()),
+        AccessError::NotOwner,
+    );
+}
+
+impl SRC14 for Contract {
+    #[storage(read, write)]
+    fn _set_proxy_target(new_target: ContractId) {
+        only_proxy_owner();
+        let old_target = storage.target.read();
+        storage.target.write(Some(new_target));
+
+        log(ProxyTargetSet {
+            new_target: new_target,
+        });
+        log(Upgraded {
+            old_target: old_target,
+            new_target: new_target,
+        });
+    }
+
+    #[storage(read)]
+    fn _proxy_target() -> Option<ContractId> {
+        storage.target.read()
+    }
+}
+
+impl SRC14Extension for Contract {
+    #[storage(read)]
+    fn _proxy_owner() -> State {
+        storage.proxy_owner.read()
+    }
+
+    #[storage(read, write)]
+    fn _set_proxy_owner(new_proxy_owner: State) {
+        only_proxy_owner();
+        storage.proxy_owner.write(new_proxy_owner);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: new_proxy_owner,
+        });
+    }
+
+    #[storage(read, write)]
+    fn renounce_upgradability() {
+        only_proxy_owner();
+        storage.proxy_owner.write(State::Revoked);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: State::Revoked,
+        });
+    }
+}
+
+impl Proxy for Contract {
+    /// Writes the configured target and owner into storage. Must be called
+    /// once after deployment before the proxy can forward anything.
+    ///
+    /// Anyone may call it: only the values fixed at deployment are written,
+    /// so a stranger calling first gains nothing over the deployer.
+    #[storage(read, write)]
+    fn initialize_proxy() {
+        require(
+            storage
+                .proxy_owner
+                .read() == State::Uninitialized,
+            ProxyError::AlreadyInitialized,
+        );
+
+        storage.target.write(INITIAL_TARGET);
+        storage.proxy_owner.write(INITIAL_OWNER);
+
+        if let Some(target) = INITIAL_TARGET {
+            log(ProxyTargetSet {
+                new_target: target,
+            });
+        }
+        log(ProxyOwnerSet {
+            new_proxy_owner: INITIAL_OWNER,
+        });
+    }
+}
+
+/// Every call that doesn't match a proxy function is run against the
+/// implementation's code in the context of this contract's storage.
+#[fallback]
+#[storage(read)]
+fn fallback() {
+    let target = storage.target.read();
+    require(target.is_some(), ProxyError::NotInitialized);
+    run_external(target.unwrap())
+}
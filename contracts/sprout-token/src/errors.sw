@@ -0,0 +1,11 @@
+library;
+
+pub enum Error {
+    AddressZero: (),
+    Unauthorized: Identity,
+    OwnerAlreadyInitialized: (),
+    OwnerNotInitialized: (),
+    IncorrectAmountProvided: u64,
+    IncorrectAssetProvided: AssetId,
+    InsufficientSupply: u64,
+}
@@ -0,0 +1,11 @@
+library;
+
+pub struct OwnerUpdated {
+    pub old_owner: Option<Identity>,
+    pub new_owner: Identity,
+}
+
+pub struct MainContractUpdated {
+    pub old_contract: ContractId,
+    pub new_contract: ContractId,
+}
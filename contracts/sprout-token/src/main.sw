@@ -0,0 +1,208 @@
+contract;
+
+mod events;
+mod errors;
+
+use ::events::*;
+use ::errors::*;
+
+use std::{
+    asset::{
+        burn,
+        mint_to,
+    },
+    call_frames::msg_asset_id,
+    constants::ZERO_B256,
+    context::msg_amount,
+    auth::msg_sender,
+    string::String,
+};
+
+use interfaces::sprout_token::Sprout_Token;
+use standards::src20::{SetDecimalsEvent, SetNameEvent, SetSymbolEvent, TotalSupplyEvent};
+
+// Every receipt asset of the token shares the same metadata
+configurable {
+    DECIMALS: u8 = 9u8,
+    NAME: str[12] = __to_str_array("Sprout Token"),
+    SYMBOL: str[4] = __to_str_array("SPRT"),
+}
+
+storage {
+    owner: Option<Identity> = None,
+    // The only contract allowed to mint and burn, usually the strategy
+    main_contract: ContractId = ContractId::from(ZERO_B256),
+    total_supply: StorageMap<AssetId, u64> = StorageMap {},
+    total_assets: u64 = 0,
+}
+
+#[storage(read)]
+fn only_owner() {
+    let sender = msg_sender().unwrap();
+    let owner = storage.owner.read();
+    require(
+        owner.is_some() && owner.unwrap() == sender,
+        Error::Unauthorized(sender),
+    );
+}
+
+#[storage(read)]
+fn only_main_contract() {
+    let sender = msg_sender().unwrap();
+    require(
+        sender == Identity::ContractId(storage.main_contract.read()),
+        Error::Unauthorized(sender),
+    );
+}
+
+#[storage(read)]
+fn is_minted(asset: AssetId) -> bool {
+    storage.total_supply.get(asset).try_read().is_some()
+}
+
+impl Sprout_Token for Contract {
+    #[storage(read)]
+    fn main_contract() -> ContractId {
+        storage.main_contract.read()
+    }
+
+    #[storage(read, write)]
+    fn mint(user: Identity, sub_id: SubId, amount: u64) {
+        only_main_contract();
+
+        let asset = AssetId::new(ContractId::this(), sub_id);
+        let sender = msg_sender().unwrap();
+
+        // Announce the metadata the first time an asset is minted
+        if !is_minted(asset) {
+            storage.total_assets.write(storage.total_assets.read() + 1);
+
+            SetNameEvent::new(asset, Some(String::from_ascii_str(from_str_array(NAME))), sender).log();
+            SetSymbolEvent::new(asset, Some(String::from_ascii_str(from_str_array(SYMBOL))), sender).log();
+            SetDecimalsEvent::new(asset, DECIMALS, sender).log();
+        }
+
+        let new_supply = storage.total_supply.get(asset).try_read().unwrap_or(0) + amount;
+        storage.total_supply.insert(asset, new_supply);
+
+        mint_to(user, sub_id, amount);
+
+        TotalSupplyEvent::new(asset, new_supply, sender).log();
+    }
+
+    #[storage(read, write), payable]
+    fn burn(sub_id: SubId, amount: u64) {
+        only_main_contract();
+
+        let asset = AssetId::new(ContractId::this(), sub_id);
+        require(
+            msg_amount() == amount,
+            Error::IncorrectAmountProvided(msg_amount()),
+        );
+        require(
+            msg_asset_id() == asset,
+            Error::IncorrectAssetProvided(msg_asset_id()),
+        );
+        let supply = storage.total_supply.get(asset).try_read().unwrap_or(0);
+        require(amount <= supply, Error::InsufficientSupply(supply));
+
+        let new_supply = supply - amount;
+        storage.total_supply.insert(asset, new_supply);
+
+        burn(sub_id, amount);
+
+        TotalSupplyEvent::new(asset, new_supply, msg_sender().unwrap()).log();
+    }
+
+    #[storage(read, write)]
+    fn update_main_caller_contract(new_contract: ContractId) {
+        only_owner();
+        require(
+            new_contract != ContractId::from(ZERO_B256),
+            Error::AddressZero,
+        );
+
+        let old_contract = storage.main_contract.read();
+        storage.main_contract.write(new_contract);
+
+        log(MainContractUpdated {
+            old_contract: old_contract,
+            new_contract: new_contract,
+        });
+    }
+
+    #[storage(read, write)]
+    fn initialize_owner() -> Identity {
+        require(
+            storage.owner.read().is_none(),
+            Error::OwnerAlreadyInitialized,
+        );
+
+        let sender = msg_sender().unwrap();
+        storage.owner.write(Some(sender));
+
+        log(OwnerUpdated {
+            old_owner: None,
+            new_owner: sender,
+        });
+
+        sender
+    }
+
+    #[storage(read)]
+    fn get_owner() -> Identity {
+        let owner = storage.owner.read();
+        require(owner.is_some(), Error::OwnerNotInitialized);
+        owner.unwrap()
+    }
+
+    #[storage(read, write)]
+    fn set_owner(new_owner: Identity) {
+        only_owner();
+
+        let old_owner = storage.owner.read();
+        storage.owner.write(Some(new_owner));
+
+        log(OwnerUpdated {
+            old_owner: old_owner,
+            new_owner: new_owner,
+        });
+    }
+
+    #[storage(read)]
+    fn total_assets() -> u64 {
+        storage.total_assets.read()
+    }
+
+    #[storage(read)]
+    fn total_supply(asset: AssetId) -> Option<u64> {
+        storage.total_supply.get(asset).try_read()
+    }
+
+    #[storage(read)]
+    fn name(asset: AssetId) -> Option<String> {
+        if is_minted(asset) {
+            Some(String::from_ascii_str(from_str_array(NAME)))
+        } else {
+            None
+        }
+    }
+
+    #[storage(read)]
+    fn symbol(asset: AssetId) -> Option<String> {
+        if is_minted(asset) {
+            Some(String::from_ascii_str(from_str_array(SYMBOL)))
+        } else {
+            None
+        }
+    }
+
+    #[storage(read)]
+    fn decimals(asset: AssetId) -> Option<u8> {
+        if is_minted(asset) {
+            Some(DECIMALS)
+        } else {
+            None
+        }
+    }
+}
@@ -15,5 +15,25 @@ pub enum Error {
     InvalidTokenAllocationPercentages: (),
     Unauthorized: Identity,
     AllocationAlreadyInitialized: (),
+    ProxyRevoked: (),
+    NotProxyOwner: Identity,
+    AlreadyInitialized: (),
+    OwnerAlreadyInitialized: (),
+    OwnerNotInitialized: (),
+    IncorrectSubId: (),
+    IncorrectAmountProvided: u64,
+    IncorrectAssetProvided: AssetId,
+    MaxSupplyReached: u64,
+    InsufficientSupply: u64,
+    ProtectedAsset: AssetId,
+    InvalidSweepAmount: u64,
+    InsufficientBalance: u64,
+    VaultNotFound: SubId,
+    VaultAlreadyExists: SubId,
+    ReceiptTokenLocked: (),
+    InvalidSignature: (),
+    SignatureExpired: u64,
+    InvalidNonce: u64,
+    InsufficientEscrow: u64,
+    NoPoolLiquidity: (AssetId, AssetId, bool),
 }
-
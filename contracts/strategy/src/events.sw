@@ -1,5 +1,7 @@
 library;
 
+use standards::src5::State;
+
 pub struct TokenAllocation {
 
     pub token: AssetId,
@@ -7,19 +9,16 @@ pub struct TokenAllocation {
     pub percentage: u64,
 }
 
-pub struct Deposit {
-    pub amount: u64,
-    pub sender: Identity,
-}
-
-
-pub struct Withdraw {
+/// Logged alongside the SRC6 `Withdraw` event when a withdrawal fee is sent
+/// to the treasury.
+pub struct WithdrawalFeeCollected {
+    pub vault_id: SubId,
     pub amount: u64,
-    pub fee_collected: u64,
-    pub recipient: Identity,
+    pub treasury: Identity,
 }
 
 pub struct WithdrawalFeeUpdated {
+    pub vault_id: SubId,
     pub old_fee: u64,
     pub new_fee: u64,
 }
@@ -56,5 +55,53 @@ pub struct StrategyInitialized {
    pub owner: Identity,
 }
 
+/// Logged when a new vault and its receipt asset are registered.
+pub struct VaultCreated {
+    pub vault_id: SubId,
+    pub asset: AssetId,
+}
+
+/// Logged when the owner recovers an asset that is not part of the basket.
+pub struct Swept {
+    pub asset: AssetId,
+    pub amount: u64,
+    pub recipient: Identity,
+}
+
+/// Logged when an owner hands shares to the strategy for signed withdrawals.
+pub struct SharesEscrowed {
+    pub owner: Identity,
+    pub vault_id: SubId,
+    pub amount: u64,
+}
+
+/// Logged when an owner takes escrowed shares back.
+pub struct SharesReleased {
+    pub owner: Identity,
+    pub vault_id: SubId,
+    pub amount: u64,
+}
+
+/// Logged when escrowed shares are redeemed with the owner's signature.
+pub struct SignedWithdrawal {
+    pub owner: Address,
+    pub receiver: Identity,
+    pub vault_id: SubId,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+pub struct Upgraded {
+    pub old_target: Option<ContractId>,
+    pub new_target: ContractId,
+}
 
+/// SRC14 event logged whenever the proxy target changes.
+pub struct ProxyTargetSet {
+    pub new_target: ContractId,
+}
 
+/// SRC14 event logged whenever the proxy owner changes.
+pub struct ProxyOwnerSet {
+    pub new_proxy_owner: State,
+}
@@ -10,12 +10,12 @@ use errors::*;
 
 use interfaces::sprout_token::Sprout_Token;
 use interfaces::mira_amm::MiraAMM;
-use interfaces::fungible_abi::FungibleAsset;
 use standards::src3::SRC3;
 use standards::src5::{AccessError, SRC5, State};
+use standards::src6::{Deposit as SRC6Deposit, SRC6, Withdraw as SRC6Withdraw};
+use standards::src7::{Metadata, SetMetadataEvent, SRC7};
 use standards::src20::{SRC20, SetDecimalsEvent, SetNameEvent, SetSymbolEvent, TotalSupplyEvent};
 
-//use fungible_abi::*;
 
 use std::{
     asset::{
@@ -23,6 +23,8 @@ use std::{
         mint_to,
         transfer,
     },
+    b512::B512,
+    block::timestamp,
     bytes::Bytes,
     call_frames::msg_asset_id,
     constants::DEFAULT_SUB_ID,
@@ -31,27 +33,35 @@ use std::{
         balance_of,
         msg_amount,
     },
+    ecr::ec_recover_address,
+    hash::{
+        Hash,
+        sha256,
+    },
     storage::*,
     auth::msg_sender,
+    revert::revert_with_log,
     string::String,
+    convert::TryFrom,
 };
 
+use std::storage::storage_string::*;
 use std::storage::storage_vec::*;
 
 // Token configuration
 configurable {
     DECIMALS: u8 = 9u8,
-    NAME: str[8] = __to_str_array("Swaylend"),
-    SYMBOL: str[5] = __to_str_array("SLEND"),
     MAX_SUPPLY: u64 = 1_000_000_000_000_000_000u64,
+    // Mint and burn receipts through the `sprout_receipt_token` contract instead of natively
+    EXTERNAL_RECEIPT_TOKEN: bool = false,
+    // Fixed at deployment so an uninitialized contract can't be claimed by a stranger
+    INITIAL_PROXY_OWNER: State = State::Uninitialized,
 }
 
 const SCALE: u64 = 10000;
 
-#[derive(AbiEncode)]
-struct IdentityValidationEvent {
-    error_code: u64,
-}
+// Bump whenever a new implementation is deployed behind the proxy
+const IMPLEMENTATION_VERSION: u64 = 2;
 
 pub struct TokenAllocation {
     pub token: AssetId,
@@ -60,51 +70,138 @@ pub struct TokenAllocation {
 }
 
 pub struct Rebalance {
-    pub old_alloc: StorageVec<TokenAllocation>,
+    pub vault_id: SubId,
+    pub old_alloc: Vec<TokenAllocation>,
     pub new_alloc: Vec<TokenAllocation>,
 }
 
+// Contract state is namespaced so it can never collide with the SRC14 slots
+// of the proxy the implementation runs behind.
+//
+// Each vault is a basket identified by a `SubId`; its receipt token is the
+// asset minted under that sub id.
 storage {
-    withdrawal_fee: u64 = 0,
-    sprout_receipt_token: ContractId = ContractId::from(0x0000000000000000000000000000000000000000000000000000000000000000),
-    fee_treasury_contract: Identity = Identity::Address(Address::from(ZERO_B256)),
-    target_tokens: StorageVec<TokenAllocation> = StorageVec {},
-    slippage_tolerance: u64 = 500, // 5% default in basis points
-    owner: Option<Identity> = Option::None,
+    strategy {
+        sprout_receipt_token: ContractId = ContractId::from(0x0000000000000000000000000000000000000000000000000000000000000000),
+        fee_treasury_contract: Identity = Identity::Address(Address::from(ZERO_B256)),
+        slippage_tolerance: u64 = 500, // 5% default in basis points
+        owner: Option<Identity> = Option::None,
+        mira_amm_id: b256 = 0x2e40f2b244b98ed6b8204b3de0156c6961f98525c8162f80162fcf53eebd90e7,
+        token_owner: State = State::Uninitialized,
+        vaults: StorageVec<SubId> = StorageVec {},
+        // Receipt asset back to the vault it belongs to
+        asset_vaults: StorageMap<AssetId, SubId> = StorageMap {},
+        withdrawal_fees: StorageMap<SubId, u64> = StorageMap {},
+        target_tokens: StorageMap<SubId, StorageVec<TokenAllocation>> = StorageMap {},
+        // Amount of each basket token held on behalf of a vault
+        holdings: StorageMap<(SubId, AssetId), u64> = StorageMap {},
+        // SRC20 state, keyed by receipt asset
+        total_supply: StorageMap<AssetId, u64> = StorageMap {},
+        name: StorageMap<AssetId, StorageString> = StorageMap {},
+        symbol: StorageMap<AssetId, StorageString> = StorageMap {},
+        // SRC7 metadata, keyed by receipt asset
+        description: StorageMap<AssetId, StorageString> = StorageMap {},
+        icon_uri: StorageMap<AssetId, StorageString> = StorageMap {},
+        uri: StorageMap<AssetId, StorageString> = StorageMap {},
+        // Shares held for signed withdrawals, keyed by owner and vault
+        escrowed_shares: StorageMap<(Identity, SubId), u64> = StorageMap {},
+        // Next nonce each owner must sign, so a signature is used at most once
+        nonces: StorageMap<Identity, u64> = StorageMap {},
+    },
     target in 0x7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55: Option<ContractId> = None,
     proxy_owner in 0xbb79927b15d9259ea316f2ecb2297d6cc8851888a98278c0a2e03e1a091ea754: State = State::Uninitialized,
-    mira_amm_id: b256 = 0x2e40f2b244b98ed6b8204b3de0156c6961f98525c8162f80162fcf53eebd90e7,
-    // Added storage for SRC20 implementation
-    total_supply: u64 = 0,
-    token_owner: State = State::Uninitialized,
 }
 
 abi Strategy {
     #[storage(read, write)]
-    fn constructor(token_contract_id: ContractId, owner_address: Address);
+    fn constructor(
+        token_contract_id: ContractId,
+        owner_address: Address,
+        name: String,
+        symbol: String,
+        description: String,
+        icon_uri: String,
+    );
+
+    #[storage(read, write)]
+    fn create_vault(
+        vault_id: SubId,
+        name: String,
+        symbol: String,
+        description: String,
+        icon_uri: String,
+    );
+
+    #[storage(read)]
+    fn get_vaults() -> Vec<SubId>;
 
     #[storage(read, write)]
-    fn initialize_token_allocations(allocations: Vec<TokenAllocation>);
+    fn set_vault_uri(vault_id: SubId, uri: String);
+
+    #[storage(read, write)]
+    fn initialize_token_allocations(vault_id: SubId, allocations: Vec<TokenAllocation>);
     
+    #[storage(read, write)]
+    fn rebalance(vault_id: SubId, new_allocations: Vec<TokenAllocation>);
+
+    // The original entry points, for the caller's own shares. SRC6 takes the
+    // `deposit` and `withdraw` names, and both go through the same paths.
     #[storage(read, write), payable]
-    fn deposit();
+    fn vault_deposit(vault_id: SubId) -> u64;
 
     #[storage(read, write), payable]
-    fn withdraw();
+    fn vault_withdraw(vault_id: SubId) -> u64;
+
+    #[storage(read)]
+    fn sweep(asset: AssetId, amount: u64, recipient: Identity);
+
+    #[storage(read)]
+    fn get_withdrawal_fee(vault_id: SubId) -> u64;
 
     #[storage(read, write)]
-    fn rebalance(new_allocations: Vec<TokenAllocation>);
+    fn set_withdrawal_fee(vault_id: SubId, fee: u64);
+
+    // Fuel has no allowances for native assets, so shares are escrowed with
+    // the strategy and a relayer redeems them with the owner's signature
+    #[payable]
+    #[storage(read, write)]
+    fn escrow_shares(vault_id: SubId) -> u64;
+
+    #[storage(read, write)]
+    fn release_shares(vault_id: SubId, amount: u64);
 
     #[storage(read)]
-    fn get_withdrawal_fee() -> u64;
+    fn get_escrowed_shares(owner: Identity, vault_id: SubId) -> u64;
 
-    #[storage(write)]
-    fn set_withdrawal_fee(fee: u64);
+    #[storage(read)]
+    fn get_nonce(owner: Identity) -> u64;
+
+    // `deadline` is a TAI64 timestamp as returned by `std::block::timestamp`,
+    // i.e. Unix seconds plus `2^62 + 10`
+    fn withdrawal_digest(
+        owner: Address,
+        receiver: Identity,
+        vault_id: SubId,
+        amount: u64,
+        deadline: u64,
+        nonce: u64,
+    ) -> b256;
+
+    #[storage(read, write)]
+    fn withdraw_with_signature(
+        owner: Address,
+        receiver: Identity,
+        vault_id: SubId,
+        amount: u64,
+        deadline: u64,
+        nonce: u64,
+        signature: B512,
+    ) -> u64;
 
     #[storage(read)]
     fn get_sprout_receipt_token() -> ContractId;
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_sprout_receipt_token(token: ContractId);
 
     #[storage(read)]
@@ -119,14 +216,17 @@ abi Strategy {
     #[storage(read)]
     fn get_slippage_tolerance() -> u64;
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn update_slippage_tolerance(new_tolerance: u64);
 
     #[storage(read)]
-    fn get_target_tokens() -> Vec<TokenAllocation>;
+    fn get_target_tokens(vault_id: SubId) -> Vec<TokenAllocation>;
 
     #[storage(read)]
-    fn get_token_allocation(token: AssetId) -> Option<u64>;
+    fn get_token_allocation(vault_id: SubId, token: AssetId) -> Option<u64>;
+
+    #[storage(read)]
+    fn get_basket_holdings(vault_id: SubId) -> Vec<(AssetId, u64)>;
 
     #[storage(read, write)]
     fn initialize_owner() -> Identity;
@@ -134,6 +234,8 @@ abi Strategy {
     #[storage(read)]
     fn get_owner() -> Identity;
 
+    fn implementation_version() -> u64;
+
     #[storage(read, write)]
     fn set_owner(new_owner: Identity);
     
@@ -145,10 +247,7 @@ abi Strategy {
     fn max_supply(asset: AssetId) -> Option<u64>;
 
     #[storage(read)]
-    fn asset_id() -> AssetId;
-    
-    #[storage(read)]
-    fn emit_src20_events();
+    fn asset_id(vault_id: SubId) -> AssetId;
 }
 
 abi SRC14 {
@@ -164,61 +263,691 @@ abi SRC14Extension {
     #[storage(read)]
     fn _proxy_owner() -> State;
     
-    #[storage(write)]
+    #[storage(read, write)]
     fn _set_proxy_owner(new_proxy_owner: State);
+
+    #[storage(read, write)]
+    fn renounce_upgradability();
+}
+
+/// Returns the stored proxy owner, falling back to the one configured at
+/// deployment until `initialize` has persisted it.
+#[storage(read)]
+fn proxy_owner() -> State {
+    match storage.proxy_owner.read() {
+        State::Uninitialized => INITIAL_PROXY_OWNER,
+        owner_state => owner_state,
+    }
 }
 
 #[storage(read)]
 fn only_proxy_owner() {
-    let owner_state = storage.proxy_owner.read();
+    let owner_state = proxy_owner();
 
     match owner_state {
-        State::Uninitialized => {
-            // Allow the call if uninitialized
-            return;
-        },
-        State::Initialized(owner) => {
-            require(msg_sender().unwrap() == owner, "NotOwner");
-        },
         State::Revoked => {
-            revert(0);
+            revert_with_log(Error::ProxyRevoked);
+        },
+        _ => {
+            // An uninitialized owner matches nobody
+            require(
+                owner_state == State::Initialized(msg_sender().unwrap()),
+                Error::NotProxyOwner(msg_sender().unwrap()),
+            );
+        }
+    }
+}
+
+#[storage(read)]
+fn only_owner() {
+    let sender = msg_sender().unwrap();
+    require(
+        storage::strategy
+            .owner
+            .read() == Option::Some(sender),
+        Error::Unauthorized(sender),
+    );
+}
+
+/// Allocations to the base asset are held as-is rather than swapped through a pool.
+fn is_base_allocation(allocation: TokenAllocation) -> bool {
+    allocation.token == AssetId::base()
+}
+
+/// The contract that mints and burns receipt assets.
+#[storage(read)]
+fn receipt_issuer() -> ContractId {
+    if EXTERNAL_RECEIPT_TOKEN {
+        storage::strategy.sprout_receipt_token.read()
+    } else {
+        ContractId::this()
+    }
+}
+
+/// The receipt asset of a vault.
+#[storage(read)]
+fn vault_asset(vault_id: SubId) -> AssetId {
+    AssetId::new(receipt_issuer(), vault_id)
+}
+
+/// Mints receipts of a vault. The external token is a Sprout token whose main
+/// contract is this strategy, or a fungible token with the vault's sub-ID
+/// reserved for this strategy, whose `mint` and `burn` take the same arguments.
+#[storage(read)]
+fn mint_receipt(recipient: Identity, vault_id: SubId, amount: u64) {
+    if EXTERNAL_RECEIPT_TOKEN {
+        abi(Sprout_Token, receipt_issuer().into()).mint(recipient, vault_id, amount);
+    } else {
+        mint_to(recipient, vault_id, amount);
+    }
+}
+
+/// Burns receipts of a vault held by this contract.
+#[storage(read)]
+fn burn_receipt(vault_id: SubId, amount: u64) {
+    if EXTERNAL_RECEIPT_TOKEN {
+        abi(Sprout_Token, receipt_issuer().into()) {
+            asset_id: vault_asset(vault_id).into(),
+            coins: amount,
+        }.burn(vault_id, amount);
+    } else {
+        burn(vault_id, amount);
+    }
+}
+
+#[storage(read)]
+fn vault_exists(vault_id: SubId) -> bool {
+    storage::strategy.total_supply.get(vault_asset(vault_id)).try_read().is_some()
+}
+
+#[storage(read)]
+fn require_vault(vault_id: SubId) {
+    require(vault_exists(vault_id), Error::VaultNotFound(vault_id));
+}
+
+/// Registers a vault and its receipt asset. Metadata can only be written here,
+/// so it is fixed for the lifetime of the vault.
+#[storage(read, write)]
+fn register_vault(
+    vault_id: SubId,
+    name: String,
+    symbol: String,
+    description: String,
+    icon_uri: String,
+) {
+    require(!vault_exists(vault_id), Error::VaultAlreadyExists(vault_id));
+
+    let asset = vault_asset(vault_id);
+    let sender = msg_sender().unwrap();
+    storage::strategy.vaults.push(vault_id);
+    storage::strategy.asset_vaults.insert(asset, vault_id);
+    storage::strategy.total_supply.insert(asset, 0);
+    storage::strategy.name.get(asset).write_slice(name);
+    storage::strategy.symbol.get(asset).write_slice(symbol);
+    storage::strategy.description.get(asset).write_slice(description);
+    storage::strategy.icon_uri.get(asset).write_slice(icon_uri);
+
+    log(VaultCreated {
+        vault_id: vault_id,
+        asset: asset,
+    });
+
+    SetNameEvent::new(asset, Some(name), sender).log();
+    SetSymbolEvent::new(asset, Some(symbol), sender).log();
+    SetDecimalsEvent::new(asset, DECIMALS, sender).log();
+    TotalSupplyEvent::new(asset, 0, sender).log();
+    SetMetadataEvent::new(
+        asset,
+        Some(Metadata::String(description)),
+        String::from_ascii_str("description"),
+        sender,
+    )
+        .log();
+    SetMetadataEvent::new(
+        asset,
+        Some(Metadata::String(icon_uri)),
+        String::from_ascii_str("icon_uri"),
+        sender,
+    )
+        .log();
+}
+
+/// Looks up a vault's SRC7 metadata. Everything but the description and
+/// icon is derived from live state, so it can never go stale.
+#[storage(read)]
+fn vault_metadata(vault_id: SubId, key: String) -> Option<Metadata> {
+    let asset = vault_asset(vault_id);
+
+    if key == String::from_ascii_str("description") {
+        match storage::strategy.description.get(asset).read_slice() {
+            Some(description) => Some(Metadata::String(description)),
+            None => None,
+        }
+    } else if key == String::from_ascii_str("icon_uri") {
+        match storage::strategy.icon_uri.get(asset).read_slice() {
+            Some(icon_uri) => Some(Metadata::String(icon_uri)),
+            None => None,
+        }
+    } else if key == String::from_ascii_str("uri") {
+        match storage::strategy.uri.get(asset).read_slice() {
+            Some(uri) => Some(Metadata::String(uri)),
+            None => None,
         }
+    } else if key == String::from_ascii_str("basket") {
+        Some(Metadata::Bytes(basket_metadata(vault_id)))
+    } else if key == String::from_ascii_str("weights") {
+        Some(Metadata::Bytes(weights_metadata(vault_id)))
+    } else if key == String::from_ascii_str("withdrawal_fee") {
+        Some(Metadata::Int(storage::strategy.withdrawal_fees.get(vault_id).try_read().unwrap_or(0)))
+    } else if key == String::from_ascii_str("slippage_tolerance") {
+        Some(Metadata::Int(storage::strategy.slippage_tolerance.read()))
+    } else {
+        None
+    }
+}
+
+/// Logs the current value of a metadata key so indexers stay in sync.
+#[storage(read)]
+fn log_metadata(vault_id: SubId, key: String) {
+    SetMetadataEvent::new(
+        vault_asset(vault_id),
+        vault_metadata(vault_id, key),
+        key,
+        msg_sender().unwrap(),
+    )
+        .log();
+}
+
+/// The allocation weights of a vault in basis points, as 8 big-endian bytes
+/// per token in the same order as `basket`.
+#[storage(read)]
+fn weights_metadata(vault_id: SubId) -> Bytes {
+    let mut weights = Bytes::new();
+    let allocations = storage::strategy.target_tokens.get(vault_id);
+
+    let mut i = 0;
+    while i < allocations.len() {
+        let percentage = allocations.get(i).unwrap().read().percentage;
+        let mut shift = 64;
+        while shift > 0 {
+            shift -= 8;
+            weights.push(u8::try_from((percentage >> shift) & 0xff).unwrap());
+        }
+        i += 1;
+    }
+    weights
+}
+
+/// The underlying basket of a vault as its token asset ids, 32 bytes each.
+#[storage(read)]
+fn basket_metadata(vault_id: SubId) -> Bytes {
+    let mut basket = Bytes::new();
+    let allocations = storage::strategy.target_tokens.get(vault_id);
+
+    let mut i = 0;
+    while i < allocations.len() {
+        basket.append(Bytes::from(allocations.get(i).unwrap().read().token.bits()));
+        i += 1;
     }
+    basket
+}
+
+#[storage(read)]
+fn total_supply_of(asset: AssetId) -> u64 {
+    storage::strategy.total_supply.get(asset).try_read().unwrap_or(0)
 }
 
 #[storage(read)]
-fn _proxy_target() -> Option<ContractId> {
-    storage.target.read()
+fn holding(vault_id: SubId, token: AssetId) -> u64 {
+    storage::strategy.holdings.get((vault_id, token)).try_read().unwrap_or(0)
 }
 
-#[storage(write)]
-fn _set_proxy_owner(new_proxy_owner: State) {
-    storage.proxy_owner.write(new_proxy_owner);
+#[storage(read, write)]
+fn add_holding(vault_id: SubId, token: AssetId, amount: u64) {
+    storage::strategy.holdings.insert((vault_id, token), holding(vault_id, token) + amount);
+}
+
+#[storage(read, write)]
+fn remove_holding(vault_id: SubId, token: AssetId, amount: u64) {
+    storage::strategy.holdings.insert((vault_id, token), holding(vault_id, token) - amount);
+}
+
+#[storage(read)]
+fn read_allocations(vault_id: SubId) -> Vec<TokenAllocation> {
+    let mut tokens = Vec::new();
+    let allocations = storage::strategy.target_tokens.get(vault_id);
+
+    let mut i = 0;
+    while i < allocations.len() {
+        tokens.push(allocations.get(i).unwrap().read());
+        i += 1;
+    }
+    tokens
+}
+
+/// Swaps `amount` of the base asset into `allocation`'s token and returns how
+/// much of it the strategy actually received.
+#[storage(read)]
+fn swap_base_into(allocation: TokenAllocation, amount: u64) -> u64 {
+    let mira_id = storage::strategy.mira_amm_id.read();
+    // Quoted before the transfer moves the reserves
+    let min_amount_out = min_swap_output(allocation, AssetId::base(), amount);
+
+    transfer(
+        Identity::ContractId(ContractId::from(mira_id)),
+        AssetId::base(),
+        amount,
+    );
+
+    // If our target token is token1 in the pool
+    let (amount_0_out, amount_1_out) = if allocation.p_id.1 == allocation.token {
+        (0, min_amount_out) // We want token1 out
+    } else {
+        (min_amount_out, 0) // We want token0 out
+    };
+
+    let balance_before = balance_of(ContractId::this(), allocation.token);
+
+    // perform swap
+    abi(MiraAMM, mira_id).swap(
+        allocation.p_id,
+        amount_0_out,
+        amount_1_out,
+        Identity::ContractId(ContractId::this()),
+        Bytes::new(),
+    );
+
+    balance_of(ContractId::this(), allocation.token) - balance_before
+}
+
+/// Swaps `amount` of `allocation`'s token back into the base asset and returns
+/// how much base asset the strategy actually received.
+#[storage(read)]
+fn swap_into_base(allocation: TokenAllocation, amount: u64) -> u64 {
+    let mira_id = storage::strategy.mira_amm_id.read();
+    // Quoted before the transfer moves the reserves
+    let min_amount_out = min_swap_output(allocation, allocation.token, amount);
+
+    transfer(
+        Identity::ContractId(ContractId::from(mira_id)),
+        allocation.token,
+        amount,
+    );
+
+    // For swap back to base asset, we need to swap in the opposite direction
+    let (amount_0_out, amount_1_out) = if allocation.p_id.1 == allocation.token {
+        (min_amount_out, 0) // We want token0 (base asset) out
+    } else {
+        (0, min_amount_out) // We want token1 (base asset) out
+    };
+
+    let balance_before = balance_of(ContractId::this(), AssetId::base());
+
+    abi(MiraAMM, mira_id).swap(
+        allocation.p_id,
+        amount_0_out,
+        amount_1_out,
+        Identity::ContractId(ContractId::this()),
+        Bytes::new(),
+    );
+
+    balance_of(ContractId::this(), AssetId::base()) - balance_before
+}
+
+/// What `amount` of an allocation's token is worth in the base asset at its
+/// pool's spot price. Spot prices can be moved within a transaction, so this
+/// is for accounting, not a manipulation-resistant oracle.
+#[storage(read)]
+fn base_value(allocation: TokenAllocation, amount: u64) -> u64 {
+    if amount == 0 || is_base_allocation(allocation) {
+        return amount;
+    }
+
+    spot_quote(allocation, allocation.token, amount)
+}
+
+/// What `amount` of `from` is worth in the other asset of `allocation`'s pool
+/// at its spot price, or 0 if the pool has none of `from` to price it by.
+#[storage(read)]
+fn spot_quote(allocation: TokenAllocation, from: AssetId, amount: u64) -> u64 {
+    let pool = abi(MiraAMM, storage::strategy.mira_amm_id.read()).pool_metadata(allocation.p_id);
+    if pool.is_none() {
+        return 0;
+    }
+    let pool = pool.unwrap();
+
+    let (reserve_in, reserve_out) = if allocation.p_id.1 == from {
+        (pool.reserve_1, pool.reserve_0)
+    } else {
+        (pool.reserve_0, pool.reserve_1)
+    };
+    if reserve_in == 0 {
+        return 0;
+    }
+    u64::try_from(amount.as_u256() * reserve_out.as_u256() / reserve_in.as_u256()).unwrap()
+}
+
+/// The least a swap of `amount` of `from` through `allocation`'s pool may
+/// return: its spot quote less the slippage tolerance. Reverts if the pool
+/// can't quote the swap, rather than letting it go through for nothing.
+#[storage(read)]
+fn min_swap_output(allocation: TokenAllocation, from: AssetId, amount: u64) -> u64 {
+    let quote = spot_quote(allocation, from, amount);
+    require(quote > 0, Error::NoPoolLiquidity(allocation.p_id));
+
+    let slippage = storage::strategy.slippage_tolerance.read();
+    quote - (quote * slippage / SCALE)
+}
+
+/// Everything a vault holds, valued in the base asset.
+#[storage(read)]
+fn vault_value(vault_id: SubId) -> u64 {
+    let allocations = read_allocations(vault_id);
+    let mut value = 0;
+
+    let mut i = 0;
+    while i < allocations.len() {
+        let allocation = allocations.get(i).unwrap();
+        value += base_value(allocation, holding(vault_id, allocation.token));
+        i += 1;
+    }
+    value
+}
+
+/// Takes the base asset forwarded with the call into a vault and mints shares
+/// for the value it added, priced against the vault's value before the
+/// deposit. Both are valued at the spot prices the swaps leave behind, so the
+/// depositor's own price impact can't dilute existing holders.
+#[storage(read, write)]
+fn deposit_into(receiver: Identity, vault_id: SubId) -> u64 {
+    require_vault(vault_id);
+    require(
+        msg_asset_id() == AssetId::base(),
+        Error::InvalidDepositAsset(msg_asset_id()),
+    );
+
+    // Get the asset and amount deposited in this transaction
+    let amount = msg_amount();
+
+    // Ensure we have target token allocations set
+    require(
+        storage::strategy.target_tokens.get(vault_id).len() > 0,
+        Error::NoCurrentTokenAllocations,
+    );
+
+    // Validate deposit amount
+    require(amount > 0, Error::InvalidDepositAmount(amount));
+    let asset = vault_asset(vault_id);
+    let supply = total_supply_of(asset);
+
+    let allocations = read_allocations(vault_id);
+    let mut received_amounts = Vec::new();
+    let mut i = 0;
+
+    // Get target distributions
+    while i < allocations.len() {
+        let allocation = allocations.get(i).unwrap();
+        let swap_amount = amount * allocation.percentage / SCALE;
+
+        if swap_amount > 0 {
+            // Base asset allocations stay in the contract
+            let received = if is_base_allocation(allocation) {
+                swap_amount
+            } else {
+                swap_base_into(allocation, swap_amount)
+            };
+            add_holding(vault_id, allocation.token, received);
+            received_amounts.push(received);
+        } else {
+            received_amounts.push(0);
+        }
+
+        i += 1;
+    }
+
+    // Only price once every swap has moved its pool
+    let mut value_added = 0;
+    i = 0;
+    while i < allocations.len() {
+        value_added += base_value(allocations.get(i).unwrap(), received_amounts.get(i).unwrap());
+        i += 1;
+    }
+    let value_before = vault_value(vault_id) - value_added;
+
+    // The first deposit sets the price at one share per base asset; after
+    // that the depositor bears their own slippage
+    let shares = if supply == 0 || value_before == 0 {
+        amount
+    } else {
+        u64::try_from(value_added.as_u256() * supply.as_u256() / value_before.as_u256()).unwrap()
+    };
+    require(shares > 0, Error::InvalidDepositAmount(amount));
+
+    // Check against max supply
+    let new_supply = supply + shares;
+    require(new_supply <= MAX_SUPPLY, Error::MaxSupplyReached(MAX_SUPPLY));
+
+    // Update total supply
+    storage::strategy.total_supply.insert(asset, new_supply);
+
+    mint_receipt(receiver, vault_id, shares);
+
+    // Log the total supply update event
+    TotalSupplyEvent::new(asset, new_supply, msg_sender().unwrap()).log();
+
+    log(SRC6Deposit {
+        caller: msg_sender().unwrap(),
+        receiver: receiver,
+        underlying_asset: AssetId::base(),
+        vault_sub_id: vault_id,
+        deposited_amount: amount,
+        minted_shares: shares,
+    });
+
+    shares
+}
+
+/// Redeems the vault shares forwarded with the call, paying `receiver`.
+#[storage(read, write)]
+fn withdraw_from(receiver: Identity, underlying_asset: AssetId, vault_id: SubId) -> u64 {
+    require_vault(vault_id);
+    // Every vault pays out in the base asset
+    require(
+        underlying_asset == AssetId::base(),
+        Error::InvalidWithdrawalAsset(underlying_asset),
+    );
+    let asset = vault_asset(vault_id);
+
+    // Ensure the received asset is this vault's receipt token
+    require(
+        msg_asset_id() == asset,
+        Error::InvalidWithdrawalAsset(msg_asset_id()),
+    );
+
+    let amount = msg_amount();
+    require(amount > 0, Error::InvalidWithdrawalAmount(amount));
+
+    redeem_shares(receiver, vault_id, amount)
+}
+
+/// Burns `amount` shares of a vault held by this contract and pays out their
+/// pro-rata part of every holding to `receiver` in the base asset, less the
+/// withdrawal fee.
+#[storage(read, write)]
+fn redeem_shares(receiver: Identity, vault_id: SubId, amount: u64) -> u64 {
+    let asset = vault_asset(vault_id);
+    let supply = total_supply_of(asset);
+    require(amount <= supply, Error::InsufficientSupply(supply));
+
+    let new_supply = supply - amount;
+    storage::strategy.total_supply.insert(asset, new_supply);
+
+    // Burn the tokens
+    burn_receipt(vault_id, amount);
+
+    // Log the total supply update event
+    TotalSupplyEvent::new(asset, new_supply, msg_sender().unwrap()).log();
+
+    let allocations = read_allocations(vault_id);
+    let mut total_base_asset = 0;
+    let mut i = 0;
+
+    while i < allocations.len() {
+        let allocation = allocations.get(i).unwrap();
+
+        // The shares' part of what the vault actually holds, never the nominal
+        // weight, so slippage and other vaults' tokens are accounted for
+        let token_amount = u64::try_from(
+            holding(vault_id, allocation.token).as_u256() * amount.as_u256() / supply.as_u256(),
+        ).unwrap();
+
+        if token_amount > 0 {
+            remove_holding(vault_id, allocation.token, token_amount);
+
+            total_base_asset += if is_base_allocation(allocation) {
+                // Already held in the base asset, pay it out directly
+                token_amount
+            } else {
+                swap_into_base(allocation, token_amount)
+            };
+        }
+        i += 1;
+    }
+
+    // Calculate and deduct withdrawal fee
+    let withdrawal_fee = storage::strategy.withdrawal_fees.get(vault_id).try_read().unwrap_or(0);
+    let fee_amount = (total_base_asset * withdrawal_fee) / SCALE;
+    let amount_after_fees = total_base_asset - fee_amount;
+
+    // Send fee to treasury if configured
+    if fee_amount > 0 {
+        let treasury = storage::strategy.fee_treasury_contract.read();
+
+        require(
+            treasury != Identity::Address(Address::from(ZERO_B256)),
+            Error::InvalidFeeTreasury,
+        );
+
+        transfer(treasury, AssetId::base(), fee_amount);
+
+        log(WithdrawalFeeCollected {
+            vault_id: vault_id,
+            amount: fee_amount,
+            treasury: treasury,
+        });
+    }
+
+    // Send remaining base asset to the receiver
+    transfer(receiver, AssetId::base(), amount_after_fees);
+
+    log(SRC6Withdraw {
+        caller: msg_sender().unwrap(),
+        receiver: receiver,
+        underlying_asset: AssetId::base(),
+        vault_sub_id: vault_id,
+        withdrawn_amount: amount_after_fees,
+        burned_shares: amount,
+    });
+
+    amount_after_fees
+}
+
+/// The message an owner signs to let anyone redeem their escrowed shares. It is
+/// bound to this contract so a signature can't be replayed on another strategy.
+fn signed_withdrawal_digest(
+    owner: Address,
+    receiver: Identity,
+    vault_id: SubId,
+    amount: u64,
+    deadline: u64,
+    nonce: u64,
+) -> b256 {
+    sha256((
+        ContractId::this(),
+        owner,
+        receiver,
+        vault_id,
+        (amount, deadline, nonce),
+    ))
+}
+
+#[storage(read)]
+fn escrowed_shares_of(owner: Identity, vault_id: SubId) -> u64 {
+    storage::strategy.escrowed_shares.get((owner, vault_id)).try_read().unwrap_or(0)
+}
+
+/// Returns whether `asset` is a receipt asset, part of any vault's basket or
+/// still held by any vault, i.e. whether it backs depositors and must never be
+/// swept. Holdings are checked on their own since they, not the basket, are
+/// what depositors are owed, the base asset included.
+#[storage(read)]
+fn is_protected_asset(asset: AssetId) -> bool {
+    let mut v = 0;
+    while v < storage::strategy.vaults.len() {
+        let vault_id = storage::strategy.vaults.get(v).unwrap().read();
+        if asset == vault_asset(vault_id) || holding(vault_id, asset) > 0 {
+            return true;
+        }
+
+        let allocations = storage::strategy.target_tokens.get(vault_id);
+        let mut i = 0;
+        while i < allocations.len() {
+            if allocations.get(i).unwrap().read().token == asset {
+                return true;
+            }
+            i += 1;
+        }
+        v += 1;
+    }
+    false
 }
 
 impl SRC14 for Contract {
     #[storage(read, write)]
     fn _set_proxy_target(new_target: ContractId) {
         only_proxy_owner(); // Add access control
+        let old_target = storage.target.read();
         storage.target.write(Some(new_target));
+
+        log(ProxyTargetSet {
+            new_target: new_target,
+        });
+        log(Upgraded {
+            old_target: old_target,
+            new_target: new_target,
+        });
     }
 
     #[storage(read)]
     fn _proxy_target() -> Option<ContractId> {
-        _proxy_target()
+        storage.target.read()
     }
 }
 
 impl SRC14Extension for Contract {
     #[storage(read)]
     fn _proxy_owner() -> State {
-        storage.proxy_owner.read()
+        proxy_owner()
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn _set_proxy_owner(new_proxy_owner: State) {
         only_proxy_owner();
-        _set_proxy_owner(new_proxy_owner);
+        storage.proxy_owner.write(new_proxy_owner);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: new_proxy_owner,
+        });
+    }
+
+    #[storage(read, write)]
+    fn renounce_upgradability() {
+        only_proxy_owner();
+        storage.proxy_owner.write(State::Revoked);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: State::Revoked,
+        });
     }
 }
 
@@ -226,42 +955,40 @@ impl SRC14Extension for Contract {
 impl SRC20 for Contract {
     #[storage(read)]
     fn total_assets() -> u64 {
-        1_u64
+        storage::strategy.vaults.len()
     }
 
     #[storage(read)]
     fn total_supply(asset: AssetId) -> Option<u64> {
-        if asset == AssetId::default() {
-            Some(storage.total_supply.read())
-        } else {
-            None
-        }
+        storage::strategy.total_supply.get(asset).try_read()
     }
 
     #[storage(read)]
     fn name(asset: AssetId) -> Option<String> {
-        if asset == AssetId::default() {
-            Some(String::from_ascii_str(from_str_array(NAME)))
-        } else {
-            None
-        }
+        storage::strategy.name.get(asset).read_slice()
     }
 
     #[storage(read)]
     fn symbol(asset: AssetId) -> Option<String> {
-        if asset == AssetId::default() {
-            Some(String::from_ascii_str(from_str_array(SYMBOL)))
-        } else {
-            None
-        }
+        storage::strategy.symbol.get(asset).read_slice()
     }
 
     #[storage(read)]
     fn decimals(asset: AssetId) -> Option<u8> {
-        if asset == AssetId::default() {
-            Some(DECIMALS)
-        } else {
-            None
+        match storage::strategy.total_supply.get(asset).try_read() {
+            Some(_) => Some(DECIMALS),
+            None => None,
+        }
+    }
+}
+
+// Implementation of SRC7 (Arbitrary asset metadata)
+impl SRC7 for Contract {
+    #[storage(read)]
+    fn metadata(asset: AssetId, key: String) -> Option<Metadata> {
+        match storage::strategy.asset_vaults.get(asset).try_read() {
+            Some(vault_id) => vault_metadata(vault_id, key),
+            None => None,
         }
     }
 }
@@ -270,7 +997,7 @@ impl SRC20 for Contract {
 impl SRC5 for Contract {
     #[storage(read)]
     fn owner() -> State {
-        storage.token_owner.read()
+        storage::strategy.token_owner.read()
     }
 }
 
@@ -278,92 +1005,213 @@ impl SRC5 for Contract {
 impl SRC3 for Contract {
     #[storage(read, write)]
     fn mint(recipient: Identity, sub_id: Option<SubId>, amount: u64) {
+        require(sub_id.is_some(), Error::IncorrectSubId);
+        let vault_id = sub_id.unwrap();
+        require_vault(vault_id);
         require(
-            sub_id
-                .is_some() && sub_id
-                .unwrap() == DEFAULT_SUB_ID,
-            "incorrect-sub-id",
-        );
-        require(
-            storage
+            storage::strategy
                 .token_owner
                 .read() == State::Initialized(msg_sender().unwrap()),
             AccessError::NotOwner,
         );
-        require(
-            storage
-                .total_supply
-                .read() + amount <= MAX_SUPPLY,
-            "max-supply-reached",
-        );
 
-        let new_supply = storage.total_supply.read() + amount;
-        storage.total_supply.write(new_supply);
+        let asset = vault_asset(vault_id);
+        let new_supply = total_supply_of(asset) + amount;
+        require(new_supply <= MAX_SUPPLY, Error::MaxSupplyReached(MAX_SUPPLY));
+        storage::strategy.total_supply.insert(asset, new_supply);
 
-        mint_to(recipient, DEFAULT_SUB_ID, amount);
+        mint_receipt(recipient, vault_id, amount);
 
-        TotalSupplyEvent::new(AssetId::default(), new_supply, msg_sender().unwrap())
+        TotalSupplyEvent::new(asset, new_supply, msg_sender().unwrap())
             .log();
     }
 
     #[payable]
     #[storage(read, write)]
     fn burn(sub_id: SubId, amount: u64) {
-        require(sub_id == DEFAULT_SUB_ID, "incorrect-sub-id");
-        require(msg_amount() == amount, "incorrect-amount-provided");
+        require_vault(sub_id);
+        let asset = vault_asset(sub_id);
+        require(
+            msg_amount() == amount,
+            Error::IncorrectAmountProvided(msg_amount()),
+        );
         require(
-            msg_asset_id() == AssetId::default(),
-            "incorrect-asset-provided",
+            msg_asset_id() == asset,
+            Error::IncorrectAssetProvided(msg_asset_id()),
         );
+        let supply = total_supply_of(asset);
+        require(amount <= supply, Error::InsufficientSupply(supply));
 
-        let new_supply = storage.total_supply.read() - amount;
-        storage.total_supply.write(new_supply);
+        let new_supply = supply - amount;
+        storage::strategy.total_supply.insert(asset, new_supply);
 
-        burn(DEFAULT_SUB_ID, amount);
+        burn_receipt(sub_id, amount);
 
-        TotalSupplyEvent::new(AssetId::default(), new_supply, msg_sender().unwrap())
+        TotalSupplyEvent::new(asset, new_supply, msg_sender().unwrap())
             .log();
     }
 }
 
+// Implementation of SRC6 (Vault standard). Each vault takes and pays out the
+// base asset and mints its own receipt asset as shares.
+impl SRC6 for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn deposit(receiver: Identity, vault_sub_id: SubId) -> u64 {
+        deposit_into(receiver, vault_sub_id)
+    }
+
+    #[payable]
+    #[storage(read, write)]
+    fn withdraw(receiver: Identity, underlying_asset: AssetId, vault_sub_id: SubId) -> u64 {
+        withdraw_from(receiver, underlying_asset, vault_sub_id)
+    }
+
+    #[storage(read)]
+    fn managed_assets(underlying_asset: AssetId, vault_sub_id: SubId) -> u64 {
+        if underlying_asset == AssetId::base() && vault_exists(vault_sub_id) {
+            vault_value(vault_sub_id)
+        } else {
+            0
+        }
+    }
+
+    #[storage(read)]
+    fn max_depositable(
+        _receiver: Identity,
+        underlying_asset: AssetId,
+        vault_sub_id: SubId,
+    ) -> Option<u64> {
+        if underlying_asset != AssetId::base() || !vault_exists(vault_sub_id) || storage::strategy.target_tokens.get(vault_sub_id).len() == 0 {
+            return None;
+        }
+
+        // The share headroom, in the base asset it takes to mint it at the
+        // vault's current share price
+        let supply = total_supply_of(vault_asset(vault_sub_id));
+        let headroom = MAX_SUPPLY - supply;
+        let value = vault_value(vault_sub_id);
+        if supply == 0 || value == 0 {
+            // The first deposit mints one share per base asset
+            return Some(headroom);
+        }
+
+        let max_amount = headroom.as_u256() * value.as_u256() / supply.as_u256();
+        if max_amount > u64::max().as_u256() {
+            Some(u64::max())
+        } else {
+            Some(u64::try_from(max_amount).unwrap())
+        }
+    }
+
+    #[storage(read)]
+    fn max_withdrawable(underlying_asset: AssetId, vault_sub_id: SubId) -> Option<u64> {
+        if underlying_asset != AssetId::base() || !vault_exists(vault_sub_id) {
+            return None;
+        }
+
+        // Redeeming every share pays out the vault's value less the withdrawal fee
+        let withdrawal_fee = storage::strategy.withdrawal_fees.get(vault_sub_id).try_read().unwrap_or(0);
+        let value = vault_value(vault_sub_id);
+        Some(value - value * withdrawal_fee / SCALE)
+    }
+}
+
 impl Strategy for Contract {
     #[storage(read, write)]
-    fn constructor(token_contract_id: ContractId, owner_address: Address) {
-        storage.sprout_receipt_token.write(token_contract_id);
-        // Verify the token contract is valid by trying to call a method
-        let receipt_token = abi(FungibleAsset, token_contract_id.into());
+    fn constructor(
+        token_contract_id: ContractId,
+        owner_address: Address,
+        name: String,
+        symbol: String,
+        description: String,
+        icon_uri: String,
+    ) {
+        // Reachable through the proxy, so only the owner configured at
+        // deployment may run it, and only once
+        only_proxy_owner();
+        require(
+            storage::strategy
+                .token_owner
+                .read() == State::Uninitialized,
+            Error::AlreadyInitialized,
+        );
+
+        // Receipt asset ids are derived from the external token, so it must be set first
+        require(
+            !EXTERNAL_RECEIPT_TOKEN || token_contract_id != ContractId::from(ZERO_B256),
+            Error::AddressZero,
+        );
+        storage::strategy.sprout_receipt_token.write(token_contract_id);
         
         // Initialize token owner
-        storage.token_owner.write(State::Initialized(Identity::Address(owner_address)));
+        storage::strategy.token_owner.write(State::Initialized(Identity::Address(owner_address)));
+
+        // The default vault always exists
+        register_vault(DEFAULT_SUB_ID, name, symbol, description, icon_uri);
+    }
+
+    #[storage(read, write)]
+    fn create_vault(
+        vault_id: SubId,
+        name: String,
+        symbol: String,
+        description: String,
+        icon_uri: String,
+    ) {
+        only_owner();
+        register_vault(vault_id, name, symbol, description, icon_uri);
+    }
+
+    #[storage(read)]
+    fn get_vaults() -> Vec<SubId> {
+        storage::strategy.vaults.load_vec()
+    }
+
+    #[storage(read, write)]
+    fn set_vault_uri(vault_id: SubId, uri: String) {
+        only_owner();
+        require_vault(vault_id);
+
+        storage::strategy.uri.get(vault_asset(vault_id)).write_slice(uri);
+        log_metadata(vault_id, String::from_ascii_str("uri"));
     }
     
     #[storage(read, write)]
     fn initialize(initial_target: ContractId) {
+        // Only the owner configured at deployment can initialize
+        only_proxy_owner();
+
         // Check if already initialized
         let current_state = storage.proxy_owner.read();
         match current_state {
             State::Uninitialized => {
-                // Set the initial owner
-                storage.proxy_owner.write(State::Initialized(msg_sender().unwrap()));
+                // Persist the configured owner
+                storage.proxy_owner.write(INITIAL_PROXY_OWNER);
                 // Set the initial target
                 storage.target.write(Some(initial_target));
+
+                log(ProxyOwnerSet {
+                    new_proxy_owner: INITIAL_PROXY_OWNER,
+                });
+                log(ProxyTargetSet {
+                    new_target: initial_target,
+                });
             },
             _ => {
-                revert(0);
+                revert_with_log(Error::AlreadyInitialized);
             }
         };
     }
 
     #[storage(read, write)]
-    fn initialize_token_allocations(allocations: Vec<TokenAllocation>) {
-        require(
-            msg_sender().unwrap() == storage.owner.read().unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+    fn initialize_token_allocations(vault_id: SubId, allocations: Vec<TokenAllocation>) {
+        only_owner();
+        require_vault(vault_id);
         
         // Ensure no existing allocations
         require(
-            storage.target_tokens.len() == 0,
+            storage::strategy.target_tokens.get(vault_id).len() == 0,
             Error::AllocationAlreadyInitialized,
         );
         
@@ -382,29 +1230,34 @@ impl Strategy for Contract {
         // Store allocations
         i = 0;
         while i < allocations.len() {
-            storage.target_tokens.push(allocations.get(i).unwrap());
+            storage::strategy.target_tokens.get(vault_id).push(allocations.get(i).unwrap());
             i += 1;
         }
         
         // Log the allocation initialization
         log(Rebalance {
-            old_alloc: StorageVec {}, // Empty since this is initialization
+            vault_id: vault_id,
+            old_alloc: Vec::new(), // Empty since this is initialization
             new_alloc: allocations,
         });
+        log_metadata(vault_id, String::from_ascii_str("basket"));
+        log_metadata(vault_id, String::from_ascii_str("weights"));
     }
     
 
     #[storage(read, write)]
     fn initialize_owner() -> Identity {
-        let owner = storage.owner.try_read().unwrap();
+        // Nobody but the deployer can race to claim ownership
+        only_proxy_owner();
+        let owner = storage::strategy.owner.try_read().unwrap();
 
         // make sure the owner has NOT already been initialized
-        require(owner.is_none(), "owner already initialized");
+        require(owner.is_none(), Error::OwnerAlreadyInitialized);
 
         // get the identity of the sender        
         let sender = msg_sender().unwrap();
         // set the owner to the sender's identity
-        storage.owner.write(Option::Some(sender));
+        storage::strategy.owner.write(Option::Some(sender));
 
         log(OwnerUpdated {
             old_owner: Identity::Address(Address::from(ZERO_B256)),
@@ -415,23 +1268,22 @@ impl Strategy for Contract {
         return sender;
     }
 
+    fn implementation_version() -> u64 {
+        IMPLEMENTATION_VERSION
+    }
+
     #[storage(read)]
     fn get_owner() -> Identity {
-        storage.owner.read().unwrap()
+        let owner = storage::strategy.owner.read();
+        require(owner.is_some(), Error::OwnerNotInitialized);
+        owner.unwrap()
     }
 
     #[storage(read, write)]
     fn set_owner(new_owner: Identity) {
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
-        let old_owner = storage.owner.read().unwrap();
-        storage.owner.write(Option::Some(new_owner));
+        only_owner();
+        let old_owner = storage::strategy.owner.read().unwrap();
+        storage::strategy.owner.write(Option::Some(new_owner));
 
         log(OwnerUpdated {
             old_owner: old_owner,
@@ -440,52 +1292,146 @@ impl Strategy for Contract {
     }
 
     #[storage(read)]
-    fn get_withdrawal_fee() -> u64 {
-        storage.withdrawal_fee.read()
+    fn get_withdrawal_fee(vault_id: SubId) -> u64 {
+        storage::strategy.withdrawal_fees.get(vault_id).try_read().unwrap_or(0)
     }
 
-    #[storage(write)]
-    fn set_withdrawal_fee(fee: u64) {
+    #[storage(read, write)]
+    fn set_withdrawal_fee(vault_id: SubId, fee: u64) {
         require(fee <= SCALE, Error::InvalidPercentage(fee));
 
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
+        require_vault(vault_id);
 
-        let old_fee = storage.withdrawal_fee.read();
-        storage.withdrawal_fee.write(fee);
+        let old_fee = storage::strategy.withdrawal_fees.get(vault_id).try_read().unwrap_or(0);
+        storage::strategy.withdrawal_fees.insert(vault_id, fee);
 
         log(WithdrawalFeeUpdated {
+            vault_id: vault_id,
             old_fee: old_fee,
             new_fee: fee,
         });
+        log_metadata(vault_id, String::from_ascii_str("withdrawal_fee"));
+    }
+
+    #[payable]
+    #[storage(read, write)]
+    fn escrow_shares(vault_id: SubId) -> u64 {
+        require_vault(vault_id);
+        require(
+            msg_asset_id() == vault_asset(vault_id),
+            Error::IncorrectAssetProvided(msg_asset_id()),
+        );
+        let amount = msg_amount();
+        require(amount > 0, Error::IncorrectAmountProvided(amount));
+
+        let owner = msg_sender().unwrap();
+        let escrowed = escrowed_shares_of(owner, vault_id) + amount;
+        storage::strategy.escrowed_shares.insert((owner, vault_id), escrowed);
+
+        log(SharesEscrowed {
+            owner: owner,
+            vault_id: vault_id,
+            amount: amount,
+        });
+
+        escrowed
+    }
+
+    #[storage(read, write)]
+    fn release_shares(vault_id: SubId, amount: u64) {
+        let owner = msg_sender().unwrap();
+        let escrowed = escrowed_shares_of(owner, vault_id);
+        require(amount <= escrowed, Error::InsufficientEscrow(escrowed));
+
+        storage::strategy.escrowed_shares.insert((owner, vault_id), escrowed - amount);
+        transfer(owner, vault_asset(vault_id), amount);
+
+        log(SharesReleased {
+            owner: owner,
+            vault_id: vault_id,
+            amount: amount,
+        });
+    }
+
+    #[storage(read)]
+    fn get_escrowed_shares(owner: Identity, vault_id: SubId) -> u64 {
+        escrowed_shares_of(owner, vault_id)
+    }
+
+    #[storage(read)]
+    fn get_nonce(owner: Identity) -> u64 {
+        storage::strategy.nonces.get(owner).try_read().unwrap_or(0)
+    }
+
+    fn withdrawal_digest(
+        owner: Address,
+        receiver: Identity,
+        vault_id: SubId,
+        amount: u64,
+        deadline: u64,
+        nonce: u64,
+    ) -> b256 {
+        signed_withdrawal_digest(owner, receiver, vault_id, amount, deadline, nonce)
+    }
+
+    #[storage(read, write)]
+    fn withdraw_with_signature(
+        owner: Address,
+        receiver: Identity,
+        vault_id: SubId,
+        amount: u64,
+        deadline: u64,
+        nonce: u64,
+        signature: B512,
+    ) -> u64 {
+        require_vault(vault_id);
+        require(timestamp() <= deadline, Error::SignatureExpired(deadline));
+
+        let owner_identity = Identity::Address(owner);
+        let expected_nonce = storage::strategy.nonces.get(owner_identity).try_read().unwrap_or(0);
+        require(nonce == expected_nonce, Error::InvalidNonce(expected_nonce));
+
+        let digest = signed_withdrawal_digest(owner, receiver, vault_id, amount, deadline, nonce);
+        let signed_by_owner = match ec_recover_address(signature, digest) {
+            Ok(signer) => signer == owner,
+            Err(_) => false,
+        };
+        require(signed_by_owner, Error::InvalidSignature);
+        storage::strategy.nonces.insert(owner_identity, nonce + 1);
+
+        require(amount > 0, Error::InvalidWithdrawalAmount(amount));
+        let escrowed = escrowed_shares_of(owner_identity, vault_id);
+        require(amount <= escrowed, Error::InsufficientEscrow(escrowed));
+        storage::strategy.escrowed_shares.insert((owner_identity, vault_id), escrowed - amount);
+
+        log(SignedWithdrawal {
+            owner: owner,
+            receiver: receiver,
+            vault_id: vault_id,
+            amount: amount,
+            nonce: nonce,
+        });
+
+        redeem_shares(receiver, vault_id, amount)
     }
 
     #[storage(read)]
     fn get_sprout_receipt_token() -> ContractId {
-        storage.sprout_receipt_token.read()
+        storage::strategy.sprout_receipt_token.read()
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn set_sprout_receipt_token(token: ContractId) {
         require(token != ContractId::from(ZERO_B256), Error::AddressZero);
 
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
+
+        // Existing receipts and vault asset ids belong to the current token
+        require(!EXTERNAL_RECEIPT_TOKEN, Error::ReceiptTokenLocked);
 
-        let old_sprout_receipt_address = storage.sprout_receipt_token.read();
-        storage.sprout_receipt_token.write(token);
+        let old_sprout_receipt_address = storage::strategy.sprout_receipt_token.read();
+        storage::strategy.sprout_receipt_token.write(token);
 
         log(ReceiptTokenUpdated {
             old_token: old_sprout_receipt_address,
@@ -495,7 +1441,7 @@ impl Strategy for Contract {
 
     #[storage(read)]
     fn get_fee_treasury_contract() -> Identity {
-        storage.fee_treasury_contract.read()
+        storage::strategy.fee_treasury_contract.read()
     }
 
     #[storage(write)]
@@ -505,17 +1451,10 @@ impl Strategy for Contract {
             treasury != Identity::Address(Address::from(ZERO_B256)),
             Error::AddressZero,
         );
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
 
-        let old_treasury = storage.fee_treasury_contract.read();
-        storage.fee_treasury_contract.write(treasury);
+        let old_treasury = storage::strategy.fee_treasury_contract.read();
+        storage::strategy.fee_treasury_contract.write(treasury);
 
         log(TreasuryUpdated {
             old_treasury: old_treasury,
@@ -525,76 +1464,63 @@ impl Strategy for Contract {
 
     #[storage(read, write)]
     fn set_mira_amm_contract(new_mira: ContractId) {
-        require(
-            msg_sender().unwrap() == storage.owner.read().unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
         
         // If MIRA_AMM_ID is moved to storage:
-        storage.mira_amm_id.write(new_mira.into());
+        storage::strategy.mira_amm_id.write(new_mira.into());
         
         // Log the update
         log(MiraAMMUpdated {
-            old_amm: ContractId::from(storage.mira_amm_id.read()),
+            old_amm: ContractId::from(storage::strategy.mira_amm_id.read()),
             new_amm: new_mira,
         });
     }
 
     #[storage(read)]
     fn get_slippage_tolerance() -> u64 {
-        storage.slippage_tolerance.read()
+        storage::strategy.slippage_tolerance.read()
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn update_slippage_tolerance(new_tolerance: u64) {
 
         require(
             new_tolerance <= SCALE,
             Error::InvalidPercentage(new_tolerance),
         );
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
 
-        let old_tolerance = storage.slippage_tolerance.read();
-        storage.slippage_tolerance.write(new_tolerance);
+        let old_tolerance = storage::strategy.slippage_tolerance.read();
+        storage::strategy.slippage_tolerance.write(new_tolerance);
 
         log(SlippageToleranceUpdated {
             old_tolerance: old_tolerance,
             new_tolerance: new_tolerance,
         });
-    }
-
-    #[storage(read)]
-    fn get_target_tokens() -> Vec<TokenAllocation> {
-        let mut tokens = Vec::new();
-        let vec_length = storage.target_tokens.len();
 
+        // Slippage is shared, so every vault's metadata changed
         let mut i = 0;
-        while i < vec_length {
-            let stored_allocation = storage.target_tokens.get(i).unwrap();
-            tokens.push(TokenAllocation {
-                token: stored_allocation.read().token,
-                p_id: stored_allocation.read().p_id,
-                percentage: stored_allocation.read().percentage,
-            });
+        while i < storage::strategy.vaults.len() {
+            log_metadata(
+                storage::strategy.vaults.get(i).unwrap().read(),
+                String::from_ascii_str("slippage_tolerance"),
+            );
             i += 1;
         }
-        tokens
     }
 
     #[storage(read)]
-    fn get_token_allocation(token: AssetId) -> Option<u64> {
-        let vec_length = storage.target_tokens.len();
+    fn get_target_tokens(vault_id: SubId) -> Vec<TokenAllocation> {
+        read_allocations(vault_id)
+    }
+
+    #[storage(read)]
+    fn get_token_allocation(vault_id: SubId, token: AssetId) -> Option<u64> {
+        let vec_length = storage::strategy.target_tokens.get(vault_id).len();
         let mut i = 0;
 
         while i < vec_length {
-            let stored_allocation = storage.target_tokens.get(i).unwrap();
+            let stored_allocation = storage::strategy.target_tokens.get(vault_id).get(i).unwrap();
             if stored_allocation.read().token == token {
                 return Some(stored_allocation.read().percentage);
             }
@@ -604,239 +1530,48 @@ impl Strategy for Contract {
     }
 
     #[storage(read)]
-fn max_supply(asset: AssetId) -> Option<u64> {
-    if asset == AssetId::default() {
-        Some(MAX_SUPPLY)
-    } else {
-        None
-    }
-}
-
-#[storage(read)]
-fn asset_id() -> AssetId {
-    AssetId::default()
-}
-
-    #[storage(read)]
-fn emit_src20_events() {
-    // Metadata that is stored as a configurable should only be emitted once.
-    let asset = AssetId::default();
-    let sender = msg_sender().unwrap();
-    let name = Some(String::from_ascii_str(from_str_array(NAME)));
-    let symbol = Some(String::from_ascii_str(from_str_array(SYMBOL)));
- 
-    SetNameEvent::new(asset, name, sender).log();
-    SetSymbolEvent::new(asset, symbol, sender).log();
-    SetDecimalsEvent::new(asset, DECIMALS, sender).log();
-    TotalSupplyEvent::new(asset, storage.total_supply.read(), sender).log();
-}
-
-
-    #[storage(read, write), payable]
-    fn deposit() {
-        require(
-            msg_asset_id() == AssetId::base(),
-            Error::InvalidDepositAsset(msg_asset_id()),
-        );
-
-        // Get the asset and amount deposited in this transaction
-        let amount = msg_amount();
-        
-        // Get the sender's identity directly
-        let sender_identity = msg_sender().unwrap();
-        
-        // Ensure we have target token allocations set
-        require(
-            storage.target_tokens.len() > 0,
-            Error::NoCurrentTokenAllocations,
-        );
+    fn get_basket_holdings(vault_id: SubId) -> Vec<(AssetId, u64)> {
+        let mut holdings = Vec::new();
+        let allocations = read_allocations(vault_id);
 
-        // Validate deposit amount
-        require(amount > 0, Error::InvalidDepositAmount(amount));
-        let new_supply = storage.total_supply.read() + amount;
-    
-    // Check against max supply
-    require(new_supply <= MAX_SUPPLY, "max-supply-reached");
-    
-    // Update total supply
-    storage.total_supply.write(new_supply);
-    
-    // Mint tokens to the sender
-    mint_to(sender_identity, DEFAULT_SUB_ID, amount);
-    
-    // Log the total supply update event
-    TotalSupplyEvent::new(AssetId::default(), new_supply, msg_sender().unwrap()).log();
-    
-        
         let mut i = 0;
-        let mira = abi(MiraAMM, storage.mira_amm_id.read());
-        let slippage = storage.slippage_tolerance.read();
-
-        // Get target distributions
-        while i < storage.target_tokens.len() {
-            let allocation = storage.target_tokens.get(i).unwrap();
-            let local_pool_id = allocation.read().p_id;
-            let swap_amount = amount * allocation.read().percentage / SCALE;
-
-            if swap_amount > 0 {
-                transfer(
-                    Identity::ContractId(ContractId::from(storage.mira_amm_id.read())),
-                    AssetId::base(),
-                    swap_amount,
-                );
-                let min_amount_out = swap_amount - (swap_amount * slippage / SCALE);
-
-                // If our target token is token1 in the pool
-                let (amount_0_out, amount_1_out) = if local_pool_id.1 == allocation.read().token {
-                    (0, min_amount_out) // We want token1 out
-                } else {
-                    (min_amount_out, 0) // We want token0 out
-                };
-
-                // perform swap
-                mira.swap(
-                    local_pool_id,
-                    amount_0_out,
-                    amount_1_out,
-                    Identity::ContractId(ContractId::this()),
-                    Bytes::new(),
-                );
-
-            }
-            
+        while i < allocations.len() {
+            let token = allocations.get(i).unwrap().token;
+            holdings.push((token, holding(vault_id, token)));
             i += 1;
         }
-
-        log(Deposit {
-            amount: amount,
-            sender: sender_identity,
-        });
+        holdings
     }
 
-   #[storage(read, write), payable]
-fn withdraw() {
-    // Ensure the received asset is the default asset (our own token)
-    require(
-        msg_asset_id() == AssetId::default(),
-        Error::InvalidWithdrawalAsset(msg_asset_id()),
-    );
-
-    let amount = msg_amount();
-    require(amount > 0, Error::InvalidWithdrawalAmount(amount));
-
-    let sender = match msg_sender() {
-        Ok(identity) => match identity {
-            Identity::Address(addr) => addr,
-            Identity::ContractId(_) => {
-                log(IdentityValidationEvent { error_code: 1 });
-                revert(1);
-            },
-            _ => {
-                log(IdentityValidationEvent { error_code: 2 });
-                revert(2);
-            }
-        },
-        Err(_) => {
-            log(IdentityValidationEvent { error_code: 3 });
-            revert(3);
-        }
-    };
-
-    // Use internal burn functionality instead of calling external contract
-    let new_supply = storage.total_supply.read() - amount;
-    storage.total_supply.write(new_supply);
-    
-    // Burn the tokens
-    burn(DEFAULT_SUB_ID, amount);
-    
-    // Log the total supply update event
-    TotalSupplyEvent::new(AssetId::default(), new_supply, msg_sender().unwrap()).log();
-
-    let mira = abi(MiraAMM, storage.mira_amm_id.read());
-    let slippage = storage.slippage_tolerance.read();
-    let mut total_base_asset = 0;
-    let mut i = 0;
-
-    while i < storage.target_tokens.len() {
-        let allocation = storage.target_tokens.get(i).unwrap();
-        let local_pool_id = allocation.read().p_id;
-        let token_amount = amount * allocation.read().percentage / SCALE;
-
-        if token_amount > 0 {
-            // Calculate minimum amount out based on slippage
-            let min_amount_out = token_amount - (token_amount * slippage / SCALE);
-
-            // For swap back to base asset, we need to swap in the opposite direction
-            let (amount_0_out, amount_1_out) = if local_pool_id.1 == allocation.read().token {
-                (min_amount_out, 0) // We want token0 (base asset) out
-            } else {
-                (0, min_amount_out) // We want token1 (base asset) out
-            };
-
-            // Approve and swap
-            transfer(
-                Identity::ContractId(ContractId::from(storage.mira_amm_id.read())),
-                allocation
-                    .read()
-                    .token,
-                token_amount,
-            );
-
-            // Perform swap back to base asset
-            mira.swap(
-                local_pool_id,
-                amount_0_out,
-                amount_1_out,
-                Identity::ContractId(ContractId::this()),
-                Bytes::new(),
-            );
-
-            total_base_asset += min_amount_out;
-        }
-        i += 1;
+    #[storage(read)]
+fn max_supply(asset: AssetId) -> Option<u64> {
+    match storage::strategy.total_supply.get(asset).try_read() {
+        Some(_) => Some(MAX_SUPPLY),
+        None => None,
     }
+}
 
-    // Calculate and deduct withdrawal fee
-    let fee_amount = (total_base_asset * storage.withdrawal_fee.read()) / SCALE;
-    let amount_after_fees = total_base_asset - fee_amount;
-
-    // Send fee to treasury if configured
-    if fee_amount > 0 {
-        let treasury = storage.fee_treasury_contract.read();
+    #[storage(read)]
+fn asset_id(vault_id: SubId) -> AssetId {
+    vault_asset(vault_id)
+}
 
-        require(
-            treasury != Identity::Address(Address::from(ZERO_B256)),
-            Error::InvalidFeeTreasury,
-        );
 
-        transfer(treasury, AssetId::base(), fee_amount);
+    #[storage(read, write), payable]
+    fn vault_deposit(vault_id: SubId) -> u64 {
+        deposit_into(msg_sender().unwrap(), vault_id)
     }
 
-    // Send remaining base asset to user
-    transfer(
-        Identity::Address(sender),
-        AssetId::base(),
-        amount_after_fees,
-    );
-
-    log(Withdraw {
-        amount: amount,
-        fee_collected: fee_amount,
-        recipient: Identity::Address(sender),
-    });
-}
+    #[storage(read, write), payable]
+    fn vault_withdraw(vault_id: SubId) -> u64 {
+        withdraw_from(msg_sender().unwrap(), AssetId::base(), vault_id)
+    }
 
     #[storage(read, write)]
-    fn rebalance(new_allocations: Vec<TokenAllocation>) {
+    fn rebalance(vault_id: SubId, new_allocations: Vec<TokenAllocation>) {
 
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
+        only_owner();
+        require_vault(vault_id);
         // First validate new allocations
         require(new_allocations.len() > 0, Error::EmptyTokenAllocations);
 
@@ -851,114 +1586,84 @@ fn withdraw() {
         require(total == SCALE, Error::InvalidTokenAllocationPercentages);
 
         // First swap all current tokens to base asset
-        let mira = abi(MiraAMM, storage.mira_amm_id.read());
-        let slippage = storage.slippage_tolerance.read();
+        let old_allocations = read_allocations(vault_id);
         let mut total_base_asset = 0;
         i = 0;
 
         // Swap existing tokens to base asset
-        while i < storage.target_tokens.len() {
-            let allocation = storage.target_tokens.get(i).unwrap();
-            let local_pool_id = allocation.read().p_id;
-
-            // Get current balance of this token
-            let token_balance = balance_of(
-                Identity::ContractId(ContractId::this())
-                    .as_contract_id()
-                    .unwrap(),
-                allocation
-                    .read()
-                    .token,
-            );
-
-            if token_balance > 0 {
-                // Calculate minimum amount out based on slippage
-                let min_amount_out = token_balance - (token_balance * slippage / SCALE);
-
-                // For swap back to base asset, we need to swap in the opposite direction
-                let (amount_0_out, amount_1_out) = if local_pool_id.1 == allocation.read().token {
-                    (min_amount_out, 0) // We want token0 (base asset) out
-                } else {
-                    (0, min_amount_out) // We want token1 (base asset) out
-                };
-
-                // Transfer token to Mira
-                transfer(
-                    Identity::ContractId(ContractId::from(storage.mira_amm_id.read())),
-                    allocation
-                        .read()
-                        .token,
-                    token_balance,
-                );
-
-                // Perform swap back to base asset
-                mira.swap(
-                    local_pool_id,
-                    amount_0_out,
-                    amount_1_out,
-                    Identity::ContractId(ContractId::this()),
-                    Bytes::new(),
-                );
-
-                total_base_asset += min_amount_out;
+        while i < old_allocations.len() {
+            let allocation = old_allocations.get(i).unwrap();
+
+            // Only what this vault holds, never another vault's share
+            let token_balance = holding(vault_id, allocation.token);
+            storage::strategy.holdings.insert((vault_id, allocation.token), 0);
+
+            if token_balance > 0 && is_base_allocation(allocation) {
+                // Base asset is carried over as-is
+                total_base_asset += token_balance;
+            } else if token_balance > 0 {
+                total_base_asset += swap_into_base(allocation, token_balance);
             }
             i += 1;
         }
 
-        let old_allocations = storage.target_tokens.read();
-
         // Update storage with new allocations
         // Clear existing allocations
-        while storage.target_tokens.len() > 0 {
-            storage.target_tokens.pop();
-        }
+        storage::strategy.target_tokens.get(vault_id).clear();
 
         // Add new allocations
         i = 0;
         while i < new_allocations.len() {
-            storage.target_tokens.push(new_allocations.get(i).unwrap());
+            storage::strategy.target_tokens.get(vault_id).push(new_allocations.get(i).unwrap());
             i += 1;
         }
 
         // Now swap base asset to new allocations
         i = 0;
-        while i < storage.target_tokens.len() {
-            let allocation = storage.target_tokens.get(i).unwrap();
-            let local_pool_id = allocation.read().p_id;
-            let swap_amount = total_base_asset * allocation.read().percentage / SCALE;
+        while i < new_allocations.len() {
+            let allocation = new_allocations.get(i).unwrap();
+            let swap_amount = total_base_asset * allocation.percentage / SCALE;
 
             if swap_amount > 0 {
-                transfer(
-                    Identity::ContractId(ContractId::from(storage.mira_amm_id.read())),
-                    AssetId::base(),
-                    swap_amount,
-                );
-                let min_amount_out = swap_amount - (swap_amount * slippage / SCALE);
-
-                // If our target token is token1 in the pool
-                let (amount_0_out, amount_1_out) = if local_pool_id.1 == allocation.read().token {
-                    (0, min_amount_out) // We want token1 out
+                // Base asset allocations stay in the contract
+                let received = if is_base_allocation(allocation) {
+                    swap_amount
                 } else {
-                    (min_amount_out, 0) // We want token0 out
+                    swap_base_into(allocation, swap_amount)
                 };
-
-                // perform swap
-                mira.swap(
-                    local_pool_id,
-                    amount_0_out,
-                    amount_1_out,
-                    Identity::ContractId(ContractId::this()),
-                    Bytes::new(),
-                );
+                add_holding(vault_id, allocation.token, received);
             }
             i += 1;
         }
 
         log(Rebalance {
+            vault_id: vault_id,
             old_alloc: old_allocations,
             new_alloc: new_allocations,
         });
+        log_metadata(vault_id, String::from_ascii_str("basket"));
+        log_metadata(vault_id, String::from_ascii_str("weights"));
+
+    }
+
+    #[storage(read)]
+    fn sweep(asset: AssetId, amount: u64, recipient: Identity) {
+        only_owner();
+
+        // Basket tokens and the receipt asset back depositors' shares
+        require(!is_protected_asset(asset), Error::ProtectedAsset(asset));
+        require(amount > 0, Error::InvalidSweepAmount(amount));
 
+        let balance = balance_of(ContractId::this(), asset);
+        require(amount <= balance, Error::InsufficientBalance(balance));
+
+        transfer(recipient, asset, amount);
+
+        log(Swept {
+            asset: asset,
+            amount: amount,
+            recipient: recipient,
+        });
     }
 }
 
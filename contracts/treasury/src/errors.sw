@@ -6,5 +6,19 @@ pub enum Error {
     InvalidDepositAsset: AssetId,
     InvalidAmount: (),
     StrategyNotInitialized: (),
+    StrategyAlreadyAuthorized: Identity,
+    StrategyNotAuthorized: Identity,
+    InsufficientBalance: AssetId,
+    SpendingLimitExceeded: u64,
+    InvalidPeriod: (),
+    InvalidSchedule: (),
+    StreamNotFound: u64,
+    StreamCancelled: u64,
+    InvalidPool: (),
+    SlippageExceeded: u64,
+    ProxyRevoked: (),
+    NotProxyOwner: Identity,
+    AlreadyInitialized: (),
+    OwnerAlreadyInitialized: (),
+    OwnerNotInitialized: (),
 }
-
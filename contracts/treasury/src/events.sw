@@ -1,5 +1,7 @@
 library;
 
+use standards::src5::State;
+
 pub struct ReceiveFees {
     pub amount: u64,
     pub sender: Identity,
@@ -15,9 +17,72 @@ pub struct OwnerUpdated {
     pub new_owner: Identity,
 }
 
+pub struct StrategyAdded {
+    pub strategy: Identity,
+}
+
+pub struct StrategyRemoved {
+    pub strategy: Identity,
+}
+
+pub struct TreasuryWithdrawal {
+    pub asset: AssetId,
+    pub amount: u64,
+    pub recipient: Identity,
+    pub sender: Identity,
+}
+
+pub struct TreasurerUpdated {
+    pub old_treasurer: Option<Identity>,
+    pub new_treasurer: Option<Identity>,
+}
+
+pub struct SpendingLimitUpdated {
+    pub asset: AssetId,
+    pub max_amount: u64,
+    pub period: u64,
+}
+
+pub struct StreamCreated {
+    pub stream_id: u64,
+    pub recipient: Identity,
+    pub asset: AssetId,
+    pub amount: u64,
+    pub start: u64,
+    pub end: u64,
+    pub cliff: u64,
+}
+
+pub struct StreamClaimed {
+    pub stream_id: u64,
+    pub recipient: Identity,
+    pub amount: u64,
+}
 
-pub struct StrategyUpdated {
-    pub old_strategy: Identity,
-    pub new_strategy: Identity,
+pub struct StreamCancelled {
+    pub stream_id: u64,
+    pub vested_paid: u64,
+    pub unvested_returned: u64,
 }
 
+pub struct BuybackExecuted {
+    pub strategy: ContractId,
+    pub vault_id: SubId,
+    pub amount_in: u64,
+    pub amount_burned: u64,
+}
+
+pub struct Upgraded {
+    pub old_target: Option<ContractId>,
+    pub new_target: ContractId,
+}
+
+/// SRC14 event logged whenever the proxy target changes.
+pub struct ProxyTargetSet {
+    pub new_target: ContractId,
+}
+
+/// SRC14 event logged whenever the proxy owner changes.
+pub struct ProxyOwnerSet {
+    pub new_proxy_owner: State,
+}
@@ -12,8 +12,8 @@ use std::{
         transfer,
     },
     bytes::Bytes,
+    convert::TryFrom,
     call_frames::msg_asset_id,
-    constants::DEFAULT_SUB_ID,
     constants::ZERO_B256,
     context::{
         balance_of,
@@ -21,9 +21,25 @@ use std::{
     },
     storage::*,
     auth::msg_sender,
+    revert::revert_with_log,
+    block::timestamp,
 };
 
-use standards::{src14::*, src5::*};
+use interfaces::mira_amm::{MiraAMM, PoolId};
+use interfaces::strategy_vault::StrategyVault;
+use standards::{src14::*, src5::*, src3::SRC3};
+
+// Bump whenever a new implementation is deployed behind the proxy
+const IMPLEMENTATION_VERSION: u64 = 2;
+
+// Mira's LP fee on volatile pools, in basis points
+const VOLATILE_POOL_FEE: u64 = 30;
+const FEE_SCALE: u64 = 10000;
+
+configurable {
+    // Fixed at deployment so an uninitialized contract can't be claimed by a stranger
+    INITIAL_PROXY_OWNER: State = State::Uninitialized,
+}
 
 
 abi Treasury {
@@ -37,13 +53,70 @@ abi Treasury {
     fn receive_fees();
 
     #[storage(read, write)]
-    fn initialize_strategy(strategy: Identity) -> Identity;
+    fn add_strategy(strategy: Identity);
+
+    #[storage(read, write)]
+    fn remove_strategy(strategy: Identity);
+
+    #[storage(read)]
+    fn is_strategy_authorized(strategy: Identity) -> bool;
+
+    #[storage(read)]
+    fn get_strategy_fees(strategy: Identity) -> u64;
+
+    #[storage(read, write)]
+    fn withdraw(asset: AssetId, amount: u64, recipient: Identity);
+
+    #[storage(read)]
+    fn get_treasurer() -> Option<Identity>;
+
+    #[storage(read, write)]
+    fn set_treasurer(new_treasurer: Option<Identity>);
 
     #[storage(read)]
-    fn get_strategy() -> Identity;
+    fn get_spending_limit(asset: AssetId) -> Option<SpendingLimit>;
+
+    #[storage(read, write)]
+    fn set_spending_limit(asset: AssetId, limit: Option<SpendingLimit>);
+
+    #[storage(read)]
+    fn get_spent_in_period(asset: AssetId) -> u64;
+
+    // `start`, `end` and `cliff` are TAI64 timestamps, see `Stream`
+    #[storage(read, write)]
+    fn create_stream(
+        recipient: Identity,
+        asset: AssetId,
+        amount: u64,
+        start: u64,
+        end: u64,
+        cliff: u64,
+    ) -> u64;
 
     #[storage(read, write)]
-    fn set_strategy(new_strategy: Identity);
+    fn claim_stream(stream_id: u64) -> u64;
+
+    #[storage(read, write)]
+    fn cancel_stream(stream_id: u64);
+
+    #[storage(read)]
+    fn get_stream(stream_id: u64) -> Option<Stream>;
+
+    #[storage(read)]
+    fn get_vested_amount(stream_id: u64) -> u64;
+
+    #[storage(read)]
+    fn get_reserved_amount(asset: AssetId) -> u64;
+
+    #[storage(read, write)]
+    fn buyback_and_burn(
+        strategy: ContractId,
+        vault_id: SubId,
+        mira_amm: ContractId,
+        pool_id: PoolId,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> u64;
 
     #[storage(read, write)]
     fn initialize_owner() -> Identity;
@@ -51,6 +124,8 @@ abi Treasury {
     #[storage(read)]
     fn get_owner() -> Identity;
 
+    fn implementation_version() -> u64;
+
     #[storage(read, write)]
     fn set_owner(new_owner: Identity);
     
@@ -72,79 +147,311 @@ abi SRC14Extension {
     #[storage(read)]
     fn _proxy_owner() -> State;
     
-    #[storage(write)]
+    #[storage(read, write)]
     fn _set_proxy_owner(new_proxy_owner: State);
+
+    #[storage(read, write)]
+    fn renounce_upgradability();
+}
+
+/// Caps how much of an asset `withdraw` can move within a rolling window.
+pub struct SpendingLimit {
+    /// Maximum amount that can be withdrawn per period.
+    pub max_amount: u64,
+    /// Length of the period in seconds.
+    pub period: u64,
+}
+
+/// Tracks the spending within the current period of a `SpendingLimit`.
+pub struct PeriodSpending {
+    pub period_start: u64,
+    pub spent: u64,
+}
+
+/// A linear vesting stream paid out of the treasury.
+///
+/// Times are TAI64 labels as returned by `std::block::timestamp`, i.e. Unix
+/// seconds plus `2^62 + 10`. A schedule given in plain Unix seconds lies in
+/// the past and vests at once.
+pub struct Stream {
+    pub recipient: Identity,
+    pub asset: AssetId,
+    /// Total amount released over the life of the stream.
+    pub amount: u64,
+    /// Timestamp at which vesting starts.
+    pub start: u64,
+    /// Timestamp at which the full amount has vested.
+    pub end: u64,
+    /// Nothing can be claimed before this timestamp.
+    pub cliff: u64,
+    /// Amount the recipient has already claimed.
+    pub claimed: u64,
+    pub cancelled: bool,
 }
 
+// Contract state is namespaced so it can never collide with the SRC14 slots
+// of the proxy the implementation runs behind.
 storage {
-    main_strategy_contract: Option<Identity> = Option::None,
-    owner: Option<Identity> = Option::None,
+    treasury {
+        authorized_strategies: StorageMap<Identity, bool> = StorageMap {},
+        strategy_fees: StorageMap<Identity, u64> = StorageMap {},
+        owner: Option<Identity> = Option::None,
+        treasurer: Option<Identity> = Option::None,
+        spending_limits: StorageMap<AssetId, SpendingLimit> = StorageMap {},
+        period_spending: StorageMap<AssetId, PeriodSpending> = StorageMap {},
+        streams: StorageMap<u64, Stream> = StorageMap {},
+        next_stream_id: u64 = 0,
+        // Funds committed to open streams, which `withdraw` cannot touch
+        reserved: StorageMap<AssetId, u64> = StorageMap {},
+        // Base asset fees the strategies have sent and not withdrawn yet
+        owed_fees: u64 = 0,
+    },
     target in 0x7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55: Option<ContractId> = None,
     proxy_owner in 0xbb79927b15d9259ea316f2ecb2297d6cc8851888a98278c0a2e03e1a091ea754: State = State::Uninitialized,
 }
 
+/// Returns the stored proxy owner, falling back to the one configured at
+/// deployment until `initialize` has persisted it.
+#[storage(read)]
+fn proxy_owner() -> State {
+    match storage.proxy_owner.read() {
+        State::Uninitialized => INITIAL_PROXY_OWNER,
+        owner_state => owner_state,
+    }
+}
+
 #[storage(read)]
 fn only_proxy_owner() {
-    let owner_state = storage.proxy_owner.read();
+    let owner_state = proxy_owner();
 
     match owner_state {
-        State::Uninitialized => {
-            // Allow the call if uninitialized
-            return;
-        },
-        State::Initialized(owner) => {
-            require(msg_sender().unwrap() == owner, "NotOwner");
-        },
         State::Revoked => {
-            revert(0);
+            revert_with_log(Error::ProxyRevoked);
+        },
+        _ => {
+            // An uninitialized owner matches nobody
+            require(
+                owner_state == State::Initialized(msg_sender().unwrap()),
+                Error::NotProxyOwner(msg_sender().unwrap()),
+            );
         }
     }
 }
 
 #[storage(read)]
-fn _proxy_target() -> Option<ContractId> {
-    storage.target.read()
+fn only_owner() {
+    let sender = msg_sender().unwrap();
+    require(
+        storage::treasury
+            .owner
+            .read() == Option::Some(sender),
+        Error::Unauthorized(sender),
+    );
 }
 
-#[storage(write)]
-fn _set_proxy_owner(new_proxy_owner: State) {
-    storage.proxy_owner.write(new_proxy_owner);
+#[storage(read)]
+fn only_owner_or_treasurer() {
+    let sender = msg_sender().unwrap();
+    let is_owner = storage::treasury.owner.read() == Option::Some(sender);
+    let is_treasurer = storage::treasury.treasurer.read() == Option::Some(sender);
+    require(is_owner || is_treasurer, Error::Unauthorized(sender));
+}
+
+/// Returns the spending of the period that is active at `now`, starting a
+/// new period if there is none yet or the last one has elapsed.
+#[storage(read)]
+fn current_period_spending(asset: AssetId, limit: SpendingLimit, now: u64) -> PeriodSpending {
+    match storage::treasury.period_spending.get(asset).try_read() {
+        Option::Some(spending) => {
+            if now >= spending.period_start + limit.period {
+                PeriodSpending {
+                    period_start: now,
+                    spent: 0,
+                }
+            } else {
+                spending
+            }
+        },
+        Option::None => PeriodSpending {
+            period_start: now,
+            spent: 0,
+        },
+    }
+}
+
+#[storage(read, write)]
+fn record_spending(asset: AssetId, amount: u64) {
+    let limit = storage::treasury.spending_limits.get(asset).try_read();
+    if limit.is_none() {
+        return;
+    }
+    let limit = limit.unwrap();
+
+    let spending = current_period_spending(asset, limit, timestamp());
+    let spent = spending.spent + amount;
+    require(
+        spent <= limit.max_amount,
+        Error::SpendingLimitExceeded(limit.max_amount - spending.spent),
+    );
+
+    storage::treasury.period_spending.insert(
+        asset,
+        PeriodSpending {
+            period_start: spending.period_start,
+            spent: spent,
+        },
+    );
+}
+
+/// Returns how much of `stream` has vested at `now`.
+fn vested_amount(stream: Stream, now: u64) -> u64 {
+    if now < stream.cliff {
+        return 0;
+    }
+    if now >= stream.end {
+        return stream.amount;
+    }
+
+    let elapsed = (now - stream.start).as_u256();
+    let duration = (stream.end - stream.start).as_u256();
+    let vested = stream.amount.as_u256() * elapsed / duration;
+    u64::try_from(vested).unwrap()
+}
+
+#[storage(read)]
+fn reserved_amount(asset: AssetId) -> u64 {
+    storage::treasury.reserved.get(asset).try_read().unwrap_or(0)
+}
+
+/// Everything the treasury holds of `asset` on behalf of others: stream
+/// reservations and, for the base asset, fees still owed to strategies.
+#[storage(read)]
+fn committed_amount(asset: AssetId) -> u64 {
+    let owed_fees = if asset == AssetId::base() {
+        storage::treasury.owed_fees.read()
+    } else {
+        0
+    };
+    reserved_amount(asset) + owed_fees
+}
+
+/// Reverts unless the treasury holds `amount` of `asset` on top of what is
+/// committed.
+#[storage(read)]
+fn require_uncommitted(asset: AssetId, amount: u64) {
+    require(
+        balance_of(ContractId::this(), asset) >= amount + committed_amount(asset),
+        Error::InsufficientBalance(asset),
+    );
+}
+
+/// Reverts unless the treasury holds the `amount` of committed `asset` it is
+/// about to pay out. Other commitments don't matter, so payouts keep working
+/// while the treasury is under-collateralized.
+fn require_held(asset: AssetId, amount: u64) {
+    require(
+        balance_of(ContractId::this(), asset) >= amount,
+        Error::InsufficientBalance(asset),
+    );
+}
+
+/// What a volatile pool pays out for exactly `amount_in` of the base asset,
+/// from the constant product of its reserves after the LP fee.
+fn quote_base_in(mira_amm: ContractId, pool_id: PoolId, amount_in: u64) -> u64 {
+    let pool = abi(MiraAMM, mira_amm.into()).pool_metadata(pool_id);
+    require(pool.is_some(), Error::InvalidPool);
+    let pool = pool.unwrap();
+
+    let (reserve_in, reserve_out) = if pool_id.0 == AssetId::base() {
+        (pool.reserve_0, pool.reserve_1)
+    } else {
+        (pool.reserve_1, pool.reserve_0)
+    };
+    let amount_in_after_fee = amount_in.as_u256() * (FEE_SCALE - VOLATILE_POOL_FEE).as_u256();
+    let amount_out = amount_in_after_fee * reserve_out.as_u256() / (reserve_in.as_u256() * FEE_SCALE.as_u256() + amount_in_after_fee);
+    u64::try_from(amount_out).unwrap()
+}
+
+#[storage(read)]
+fn read_stream(stream_id: u64) -> Stream {
+    let stream = storage::treasury.streams.get(stream_id).try_read();
+    require(stream.is_some(), Error::StreamNotFound(stream_id));
+    stream.unwrap()
+}
+
+#[storage(read)]
+fn is_strategy_authorized(strategy: Identity) -> bool {
+    storage::treasury.authorized_strategies.get(strategy).try_read().unwrap_or(false)
 }
 
 impl SRC14 for Contract {
     #[storage(read, write)]
     fn _set_proxy_target(new_target: ContractId) {
         only_proxy_owner(); // Add access control
+        let old_target = storage.target.read();
         storage.target.write(Some(new_target));
+
+        log(ProxyTargetSet {
+            new_target: new_target,
+        });
+        log(Upgraded {
+            old_target: old_target,
+            new_target: new_target,
+        });
     }
 
     #[storage(read)]
     fn _proxy_target() -> Option<ContractId> {
-        _proxy_target()
+        storage.target.read()
     }
 }
 
 impl SRC14Extension for Contract {
     #[storage(read)]
     fn _proxy_owner() -> State {
-        storage.proxy_owner.read()
+        proxy_owner()
     }
 
-    #[storage(write)]
+    #[storage(read, write)]
     fn _set_proxy_owner(new_proxy_owner: State) {
         only_proxy_owner();
-        _set_proxy_owner(new_proxy_owner);
+        storage.proxy_owner.write(new_proxy_owner);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: new_proxy_owner,
+        });
+    }
+
+    #[storage(read, write)]
+    fn renounce_upgradability() {
+        only_proxy_owner();
+        storage.proxy_owner.write(State::Revoked);
+
+        log(ProxyOwnerSet {
+            new_proxy_owner: State::Revoked,
+        });
     }
 }
 
 impl Treasury for Contract {
     #[storage(read, write)]
     fn constructor(owner_address: Address, strategy_identity: Identity) {
+        // Reachable through the proxy, so only the owner configured at
+        // deployment may run it, and only once
+        only_proxy_owner();
+        require(
+            storage::treasury
+                .owner
+                .read()
+                .is_none(),
+            Error::AlreadyInitialized,
+        );
+
         // Initialize the owner
-        storage.owner.write(Option::Some(Identity::Address(owner_address)));
+        storage::treasury.owner.write(Option::Some(Identity::Address(owner_address)));
         
-        // Initialize the strategy
-        storage.main_strategy_contract.write(Option::Some(strategy_identity));
+        // Register the first strategy
+        storage::treasury.authorized_strategies.insert(strategy_identity, true);
         
         // Log the initialization events
         log(OwnerUpdated {
@@ -152,49 +459,53 @@ impl Treasury for Contract {
             new_owner: Identity::Address(owner_address),
         });
         
-        log(StrategyUpdated {
-            old_strategy: Identity::Address(Address::from(ZERO_B256)),
-            new_strategy: strategy_identity,
+        log(StrategyAdded {
+            strategy: strategy_identity,
         });
     }
 
     #[storage(read, write)]
     fn initialize(initial_target: ContractId) {
+        // Only the owner configured at deployment can initialize
+        only_proxy_owner();
+
         // Check if already initialized
         let current_state = storage.proxy_owner.read();
         match current_state {
             State::Uninitialized => {
-                // Set the initial owner
-                storage
-                    .proxy_owner
-                    .write(State::Initialized(msg_sender().unwrap()));
+                // Persist the configured owner
+                storage.proxy_owner.write(INITIAL_PROXY_OWNER);
                 // Set the initial target
                 storage.target.write(Some(initial_target));
+
+                log(ProxyOwnerSet {
+                    new_proxy_owner: INITIAL_PROXY_OWNER,
+                });
+                log(ProxyTargetSet {
+                    new_target: initial_target,
+                });
             },
             _ => {
-                revert(0);
+                revert_with_log(Error::AlreadyInitialized);
             }
         };
     }
 
     #[storage(read, write)]
     fn withdraw_fees() {
-        let sender = msg_sender().unwrap(); 
-        let balance = balance_of(
-            Identity::ContractId(ContractId::this())
-                .as_contract_id()
-                .unwrap(),
-            AssetId::base(),
-        ); // Get balance of base asset
+        let sender = msg_sender().unwrap();
         require(
-            sender == storage
-                .main_strategy_contract
-                .read()
-                .unwrap(),
+            is_strategy_authorized(sender),
             Error::Unauthorized(sender),
         );
 
+        // A strategy can only withdraw the fees it sent itself
+        let balance = storage::treasury.strategy_fees.get(sender).try_read().unwrap_or(0);
         require(balance > 0, Error::InvalidAmount);
+        require_held(AssetId::base(), balance);
+
+        storage::treasury.strategy_fees.insert(sender, 0);
+        storage::treasury.owed_fees.write(storage::treasury.owed_fees.read() - balance);
 
         transfer(sender, AssetId::base(), balance);
 
@@ -206,13 +517,10 @@ impl Treasury for Contract {
 
     #[storage(read, write), payable]
     fn receive_fees() {
+        let sender = msg_sender().unwrap();
         require(
-            msg_sender()
-                .unwrap() == storage
-                .main_strategy_contract
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
+            is_strategy_authorized(sender),
+            Error::Unauthorized(sender),
         );
         require(
             msg_asset_id() == AssetId::base(),
@@ -221,57 +529,334 @@ impl Treasury for Contract {
 
         require(msg_amount() > 0, Error::InvalidAmount);
 
+        // Attribute the fees to the sending strategy
+        let accrued = storage::treasury.strategy_fees.get(sender).try_read().unwrap_or(0);
+        storage::treasury.strategy_fees.insert(sender, accrued + msg_amount());
+        storage::treasury.owed_fees.write(storage::treasury.owed_fees.read() + msg_amount());
+
         log(ReceiveFees {
             amount: msg_amount(),
+            sender: sender,
+        });
+    }
+
+    #[storage(read, write)]
+    fn add_strategy(strategy: Identity) {
+        only_owner();
+        require(
+            !is_strategy_authorized(strategy),
+            Error::StrategyAlreadyAuthorized(strategy),
+        );
+
+        storage::treasury.authorized_strategies.insert(strategy, true);
+
+        log(StrategyAdded {
+            strategy: strategy,
+        });
+    }
+
+    #[storage(read, write)]
+    fn remove_strategy(strategy: Identity) {
+        only_owner();
+        require(
+            is_strategy_authorized(strategy),
+            Error::StrategyNotAuthorized(strategy),
+        );
+
+        storage::treasury.authorized_strategies.insert(strategy, false);
+
+        log(StrategyRemoved {
+            strategy: strategy,
+        });
+    }
+
+    #[storage(read)]
+    fn is_strategy_authorized(strategy: Identity) -> bool {
+        is_strategy_authorized(strategy)
+    }
+
+    #[storage(read)]
+    fn get_strategy_fees(strategy: Identity) -> u64 {
+        storage::treasury.strategy_fees.get(strategy).try_read().unwrap_or(0)
+    }
+
+    #[storage(read, write)]
+    fn withdraw(asset: AssetId, amount: u64, recipient: Identity) {
+        only_owner_or_treasurer();
+        require(amount > 0, Error::InvalidAmount);
+        require_uncommitted(asset, amount);
+
+        record_spending(asset, amount);
+
+        transfer(recipient, asset, amount);
+
+        log(TreasuryWithdrawal {
+            asset: asset,
+            amount: amount,
+            recipient: recipient,
             sender: msg_sender().unwrap(),
         });
     }
 
+    #[storage(read)]
+    fn get_treasurer() -> Option<Identity> {
+        storage::treasury.treasurer.read()
+    }
+
     #[storage(read, write)]
-    fn initialize_strategy(strategy: Identity) -> Identity {
-        let current_strategy = storage.main_strategy_contract.try_read().unwrap();
-        require(current_strategy.is_none(), "strategy already initialized");
-        storage.main_strategy_contract.write(Option::Some(strategy));
-        
-        log(StrategyUpdated {
-            old_strategy: Identity::Address(Address::from(ZERO_B256)),
-            new_strategy: strategy,
+    fn set_treasurer(new_treasurer: Option<Identity>) {
+        only_owner();
+        let old_treasurer = storage::treasury.treasurer.read();
+        storage::treasury.treasurer.write(new_treasurer);
+
+        log(TreasurerUpdated {
+            old_treasurer: old_treasurer,
+            new_treasurer: new_treasurer,
         });
-        
-        return strategy;
     }
 
     #[storage(read)]
-    fn get_strategy() -> Identity {
-        storage.main_strategy_contract.read().unwrap()
+    fn get_spending_limit(asset: AssetId) -> Option<SpendingLimit> {
+        storage::treasury.spending_limits.get(asset).try_read()
     }
 
     #[storage(read, write)]
-    fn set_strategy(new_strategy: Identity) {
+    fn set_spending_limit(asset: AssetId, limit: Option<SpendingLimit>) {
+        only_owner();
+
+        let (max_amount, period) = match limit {
+            Option::Some(limit) => {
+                require(limit.period > 0, Error::InvalidPeriod);
+                storage::treasury.spending_limits.insert(asset, limit);
+                (limit.max_amount, limit.period)
+            },
+            Option::None => {
+                let _ = storage::treasury.spending_limits.remove(asset);
+                (0, 0)
+            },
+        };
+        // Changing the limit starts a fresh period
+        let _ = storage::treasury.period_spending.remove(asset);
+
+        log(SpendingLimitUpdated {
+            asset: asset,
+            max_amount: max_amount,
+            period: period,
+        });
+    }
+
+    #[storage(read)]
+    fn get_spent_in_period(asset: AssetId) -> u64 {
+        match storage::treasury.spending_limits.get(asset).try_read() {
+            Option::Some(limit) => current_period_spending(asset, limit, timestamp()).spent,
+            Option::None => 0,
+        }
+    }
+
+    #[storage(read, write)]
+    fn create_stream(
+        recipient: Identity,
+        asset: AssetId,
+        amount: u64,
+        start: u64,
+        end: u64,
+        cliff: u64,
+    ) -> u64 {
+        only_owner();
+        require(amount > 0, Error::InvalidAmount);
         require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
+            start < end && cliff >= start && cliff <= end,
+            Error::InvalidSchedule,
+        );
+
+        // The stream must be fully backed by uncommitted funds
+        require_uncommitted(asset, amount);
+        let reserved = reserved_amount(asset);
+        storage::treasury.reserved.insert(asset, reserved + amount);
+
+        let stream_id = storage::treasury.next_stream_id.read();
+        storage::treasury.next_stream_id.write(stream_id + 1);
+        storage::treasury.streams.insert(
+            stream_id,
+            Stream {
+                recipient: recipient,
+                asset: asset,
+                amount: amount,
+                start: start,
+                end: end,
+                cliff: cliff,
+                claimed: 0,
+                cancelled: false,
+            },
+        );
+
+        log(StreamCreated {
+            stream_id: stream_id,
+            recipient: recipient,
+            asset: asset,
+            amount: amount,
+            start: start,
+            end: end,
+            cliff: cliff,
+        });
+
+        stream_id
+    }
+
+    #[storage(read, write)]
+    fn claim_stream(stream_id: u64) -> u64 {
+        let mut stream = read_stream(stream_id);
+        let sender = msg_sender().unwrap();
+        require(sender == stream.recipient, Error::Unauthorized(sender));
+        require(!stream.cancelled, Error::StreamCancelled(stream_id));
+
+        let claimable = vested_amount(stream, timestamp()) - stream.claimed;
+        require(claimable > 0, Error::InvalidAmount);
+        require_held(stream.asset, claimable);
+
+        stream.claimed += claimable;
+        storage::treasury.streams.insert(stream_id, stream);
+        storage::treasury
+            .reserved
+            .insert(stream.asset, reserved_amount(stream.asset) - claimable);
+
+        transfer(stream.recipient, stream.asset, claimable);
+
+        log(StreamClaimed {
+            stream_id: stream_id,
+            recipient: stream.recipient,
+            amount: claimable,
+        });
+
+        claimable
+    }
+
+    #[storage(read, write)]
+    fn cancel_stream(stream_id: u64) {
+        only_owner();
+        let mut stream = read_stream(stream_id);
+        require(!stream.cancelled, Error::StreamCancelled(stream_id));
+
+        // Whatever has vested still belongs to the recipient
+        let vested = vested_amount(stream, timestamp());
+        let owed = vested - stream.claimed;
+        let unvested = stream.amount - vested;
+        require_held(stream.asset, owed);
+
+        stream.claimed = vested;
+        stream.cancelled = true;
+        storage::treasury.streams.insert(stream_id, stream);
+
+        // Release the whole remaining reservation; unvested funds return to the treasury
+        storage::treasury
+            .reserved
+            .insert(stream.asset, reserved_amount(stream.asset) - owed - unvested);
+
+        if owed > 0 {
+            transfer(stream.recipient, stream.asset, owed);
+        }
+
+        log(StreamCancelled {
+            stream_id: stream_id,
+            vested_paid: owed,
+            unvested_returned: unvested,
+        });
+    }
+
+    #[storage(read)]
+    fn get_stream(stream_id: u64) -> Option<Stream> {
+        storage::treasury.streams.get(stream_id).try_read()
+    }
+
+    #[storage(read)]
+    fn get_vested_amount(stream_id: u64) -> u64 {
+        vested_amount(read_stream(stream_id), timestamp())
+    }
+
+    #[storage(read)]
+    fn get_reserved_amount(asset: AssetId) -> u64 {
+        reserved_amount(asset)
+    }
+
+    #[storage(read, write)]
+    fn buyback_and_burn(
+        strategy: ContractId,
+        vault_id: SubId,
+        mira_amm: ContractId,
+        pool_id: PoolId,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> u64 {
+        only_owner_or_treasurer();
+        require(
+            is_strategy_authorized(Identity::ContractId(strategy)),
+            Error::StrategyNotAuthorized(Identity::ContractId(strategy)),
+        );
+        require(amount_in > 0, Error::InvalidAmount);
+        require(min_amount_out > 0, Error::InvalidAmount);
+        require_uncommitted(AssetId::base(), amount_in);
+
+        // Vaults may mint through an external token, so ask the strategy
+        let receipt_asset = abi(StrategyVault, strategy.into()).asset_id(vault_id);
+        require(
+            (pool_id.0 == AssetId::base() && pool_id.1 == receipt_asset) || (pool_id.1 == AssetId::base() && pool_id.0 == receipt_asset),
+            Error::InvalidPool,
         );
-        let old_strategy = storage.main_strategy_contract.read().unwrap();
-        storage
-            .main_strategy_contract
-            .write(Option::Some(new_strategy));
-        log(StrategyUpdated {
-            old_strategy,
-            new_strategy,
+        // Stable pools price on a curve we don't quote
+        require(!pool_id.2, Error::InvalidPool);
+
+        // Swap all of `amount_in`, asking for everything it buys
+        let quoted = quote_base_in(mira_amm, pool_id, amount_in);
+        require(quoted >= min_amount_out, Error::SlippageExceeded(quoted));
+
+        // If the receipt token is token1 in the pool
+        let (amount_0_out, amount_1_out) = if pool_id.1 == receipt_asset {
+            (0, quoted) // We want token1 out
+        } else {
+            (quoted, 0) // We want token0 out
+        };
+
+        let receipt_before = balance_of(ContractId::this(), receipt_asset);
+
+        transfer(Identity::ContractId(mira_amm), AssetId::base(), amount_in);
+        abi(MiraAMM, mira_amm.into()).swap(
+            pool_id,
+            amount_0_out,
+            amount_1_out,
+            Identity::ContractId(ContractId::this()),
+            Bytes::new(),
+        );
+
+        let amount_out = balance_of(ContractId::this(), receipt_asset) - receipt_before;
+        require(
+            amount_out >= min_amount_out,
+            Error::SlippageExceeded(amount_out),
+        );
+
+        // Burn everything we bought back through the strategy's SRC3 burn
+        abi(SRC3, strategy.into()) {
+            asset_id: receipt_asset.into(),
+            coins: amount_out,
+        }.burn(vault_id, amount_out);
+
+        log(BuybackExecuted {
+            strategy: strategy,
+            vault_id: vault_id,
+            amount_in: amount_in,
+            amount_burned: amount_out,
         });
+
+        amount_out
     }
 
     #[storage(read, write)]
     fn initialize_owner() -> Identity {
-        let owner = storage.owner.try_read().unwrap();
-        require(owner.is_none(), "owner already initialized");
+        // Nobody but the deployer can race to claim ownership
+        only_proxy_owner();
+        let owner = storage::treasury.owner.try_read().unwrap();
+        require(owner.is_none(), Error::OwnerAlreadyInitialized);
         let sender = msg_sender().unwrap();
-        storage.owner.write(Option::Some(sender));
+        storage::treasury.owner.write(Option::Some(sender));
         log(OwnerUpdated {
             old_owner: Identity::Address(Address::from(ZERO_B256)),
             new_owner: sender,
@@ -279,23 +864,22 @@ impl Treasury for Contract {
         return sender;
     }
 
+    fn implementation_version() -> u64 {
+        IMPLEMENTATION_VERSION
+    }
+
     #[storage(read)]
     fn get_owner() -> Identity {
-        storage.owner.read().unwrap()
+        let owner = storage::treasury.owner.read();
+        require(owner.is_some(), Error::OwnerNotInitialized);
+        owner.unwrap()
     }
 
     #[storage(read, write)]
     fn set_owner(new_owner: Identity) {
-        require(
-            msg_sender()
-                .unwrap() == storage
-                .owner
-                .read()
-                .unwrap(),
-            Error::Unauthorized(msg_sender().unwrap()),
-        );
-        let old_owner = storage.owner.read().unwrap();
-        storage.owner.write(Option::Some(new_owner));
+        only_owner();
+        let old_owner = storage::treasury.owner.read().unwrap();
+        storage::treasury.owner.write(Option::Some(new_owner));
 
         log(OwnerUpdated {
             old_owner: old_owner,
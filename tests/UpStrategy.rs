@@ -3,12 +3,22 @@ use fuels::{
     types::{ContractId, Identity},
 };
 
+mod common;
+use common::revert::{assert_reverted_with, assert_reverted_with_variant};
+
 
 // Load abi from json
-abigen!(Contract(
-    name = "Strategy",
-    abi = "contracts/strategy/out/debug/strategy-abi.json"
-));
+abigen!(
+    Contract(
+        name = "Strategy",
+        abi = "contracts/strategy/out/debug/strategy-abi.json"
+    ),
+    Contract(
+        name = "Proxy",
+        abi = "contracts/proxy/out/debug/proxy-abi.json"
+    ));
+
+use abigen_bindings::strategy_mod::Error as StrategyError;
 
 async fn get_contract_instance() -> (Strategy<WalletUnlocked>, ContractId, WalletUnlocked, WalletUnlocked) {
     // Launch a local network and deploy the contract
@@ -27,9 +37,14 @@ async fn get_contract_instance() -> (Strategy<WalletUnlocked>, ContractId, Walle
     let non_owner = wallets.pop().unwrap();
     let owner = wallets.pop().unwrap();
 
+    // The proxy owner is fixed at deployment
+    let configurables = StrategyConfigurables::default()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(owner.address().into())))
+        .unwrap();
+
     let id = Contract::load_from(
         "contracts/strategy/out/debug/strategy.bin",
-        LoadConfiguration::default(),
+        LoadConfiguration::default().with_configurables(configurables),
     )
     .unwrap()
     .deploy(&owner, TxPolicies::default())
@@ -41,6 +56,46 @@ async fn get_contract_instance() -> (Strategy<WalletUnlocked>, ContractId, Walle
     (instance, id.into(), owner, non_owner)
 }
 
+// Deploys an implementation with a custom salt so the same bytecode gets a new id
+async fn deploy_implementation(owner: &WalletUnlocked, salt: [u8; 32]) -> ContractId {
+    Contract::load_from(
+        "contracts/strategy/out/debug/strategy.bin",
+        LoadConfiguration::default().with_salt(salt),
+    )
+    .unwrap()
+    .deploy(owner, TxPolicies::default())
+    .await
+    .unwrap()
+    .into()
+}
+
+// Deploys a proxy forwarding to `target` and owned by `owner`
+async fn deploy_proxy(owner: &WalletUnlocked, target: ContractId) -> Bech32ContractId {
+    let configurables = ProxyConfigurables::default()
+        .with_INITIAL_TARGET(Some(target))
+        .unwrap()
+        .with_INITIAL_OWNER(State::Initialized(Identity::Address(owner.address().into())))
+        .unwrap();
+
+    let proxy_id = Contract::load_from(
+        "contracts/proxy/out/debug/proxy.bin",
+        LoadConfiguration::default().with_configurables(configurables),
+    )
+    .unwrap()
+    .deploy(owner, TxPolicies::default())
+    .await
+    .unwrap();
+
+    Proxy::new(proxy_id.clone(), owner.clone())
+        .methods()
+        .initialize_proxy()
+        .call()
+        .await
+        .unwrap();
+
+    proxy_id
+}
+
 #[tokio::test]
 async fn can_get_contract_id() {
     let (_instance, _id, _owner, _provider) = get_contract_instance().await;
@@ -121,9 +176,9 @@ async fn test_proxy_target_management() {
 }
 
 #[tokio::test]
-#[should_panic(expected = "NotOwner")]
 async fn test_only_owner_can_set_proxy_target() {
-    let (instance, _id, owner, non_owner) = get_contract_instance().await;
+    let (instance, _id, _owner, non_owner) = get_contract_instance().await;
+    let non_owner_identity = Identity::Address(non_owner.address().into());
     let initial_target = ContractId::from([1u8; 32]);
     let new_target = ContractId::from([2u8; 32]);
 
@@ -136,19 +191,18 @@ async fn test_only_owner_can_set_proxy_target() {
         .unwrap();
 
     // Try to update target with non-owner
-    instance
+    let result = instance
         .with_account(non_owner)
         .methods()
         ._set_proxy_target(new_target)
         .call()
-        .await
-        .unwrap();
+        .await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(non_owner_identity));
 }
 
 #[tokio::test]
-#[should_panic(expected = "Revert(0)")]  // Changed to match the actual revert
 async fn test_cannot_initialize_twice() {
-    let (instance, _id, owner, _non_owner) = get_contract_instance().await;
+    let (instance, _id, _owner, _non_owner) = get_contract_instance().await;
     let implementation_id = ContractId::from([1u8; 32]);
 
     // First initialization
@@ -159,11 +213,364 @@ async fn test_cannot_initialize_twice() {
         .await
         .unwrap();
 
-    // Try to initialize again - this should fail with Revert(0)
-    instance
+    // Try to initialize again - this should fail with AlreadyInitialized
+    let result = instance
         .methods()
         .initialize(implementation_id)
         .call()
+        .await;
+    assert_reverted_with(result, StrategyError::AlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_only_proxy_owner_can_construct_and_claim_ownership() {
+    let (instance, _id, owner, non_owner) = get_contract_instance().await;
+    let non_owner_identity = Identity::Address(non_owner.address().into());
+    let stranger = instance.clone().with_account(non_owner.clone());
+
+    // A stranger can't front-run the deployer
+    let result = stranger
+        .methods()
+        .constructor(
+            ContractId::from([1u8; 32]),
+            non_owner.address(),
+            "Sprout Basket".to_string(),
+            "SPRT".to_string(),
+            String::new(),
+            String::new(),
+        )
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(non_owner_identity));
+
+    let result = stranger.methods().initialize_owner().call().await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(non_owner_identity));
+
+    // The deployer can, once
+    instance
+        .methods()
+        .constructor(
+            ContractId::from([1u8; 32]),
+            owner.address(),
+            "Sprout Basket".to_string(),
+            "SPRT".to_string(),
+            String::new(),
+            String::new(),
+        )
+        .call()
+        .await
+        .unwrap();
+    let result = instance
+        .methods()
+        .constructor(
+            ContractId::from([1u8; 32]),
+            owner.address(),
+            "Sprout Basket".to_string(),
+            "SPRT".to_string(),
+            String::new(),
+            String::new(),
+        )
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::AlreadyInitialized);
+
+    instance.methods().initialize_owner().call().await.unwrap();
+    let result = instance.methods().initialize_owner().call().await;
+    assert_reverted_with(result, StrategyError::OwnerAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_upgrade_through_proxy_preserves_storage() {
+    let (implementation, implementation_id, owner, _non_owner) = get_contract_instance().await;
+    let proxy_id = deploy_proxy(&owner, implementation_id).await;
+    let proxied = Strategy::new(proxy_id, owner.clone());
+
+    // Write some state through the proxy
+    proxied
+        .methods()
+        .initialize_owner()
+        .with_contract_ids(&[implementation_id.into()])
+        .call()
+        .await
+        .unwrap();
+    proxied
+        .methods()
+        .update_slippage_tolerance(250)
+        .with_contract_ids(&[implementation_id.into()])
+        .call()
         .await
         .unwrap();
-}
\ No newline at end of file
+
+    // Point the proxy at a fresh implementation
+    let new_implementation_id = deploy_implementation(&owner, [1u8; 32]).await;
+    proxied
+        .methods()
+        ._set_proxy_target(new_implementation_id)
+        .call()
+        .await
+        .unwrap();
+
+    let proxy_target = proxied
+        .methods()
+        ._proxy_target()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(proxy_target, Some(new_implementation_id));
+
+    // The state written before the upgrade is still there
+    let slippage = proxied
+        .methods()
+        .get_slippage_tolerance()
+        .with_contract_ids(&[new_implementation_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(slippage, 250);
+
+    let proxied_owner = proxied
+        .methods()
+        .get_owner()
+        .with_contract_ids(&[new_implementation_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(proxied_owner, Identity::Address(owner.address().into()));
+
+    // The implementation's own storage was never touched
+    let implementation_slippage = implementation
+        .methods()
+        .get_slippage_tolerance()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(implementation_slippage, 500);
+}
+
+#[tokio::test]
+async fn test_only_proxy_owner_can_upgrade() {
+    let (_implementation, implementation_id, owner, non_owner) = get_contract_instance().await;
+    let proxy_id = deploy_proxy(&owner, implementation_id).await;
+    let new_implementation_id = deploy_implementation(&owner, [1u8; 32]).await;
+
+    // The proxy answers SRC14 calls itself
+    let result = Proxy::new(proxy_id, non_owner)
+        .methods()
+        ._set_proxy_target(new_implementation_id)
+        .call()
+        .await;
+
+    assert_reverted_with_variant(result, "NotOwner");
+}
+
+#[tokio::test]
+async fn test_proxy_owner_set_at_deployment() {
+    let (instance, _id, owner, _non_owner) = get_contract_instance().await;
+
+    // Ownership is in place before anyone calls `initialize`
+    let proxy_owner = instance
+        .methods()
+        ._proxy_owner()
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    assert_eq!(proxy_owner, State::Initialized(Identity::Address(owner.address().into())));
+}
+
+#[tokio::test]
+async fn test_stranger_cannot_hijack_uninitialized_contract() {
+    let (instance, _id, _owner, non_owner) = get_contract_instance().await;
+    let stranger_target = ContractId::from([9u8; 32]);
+    let stranger_identity = Identity::Address(non_owner.address().into());
+    let stranger = State::Initialized(stranger_identity);
+    let instance = instance.with_account(non_owner);
+
+    let result = instance
+        .methods()
+        ._set_proxy_target(stranger_target)
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(stranger_identity));
+
+    let result = instance
+        .methods()
+        ._set_proxy_owner(stranger)
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(stranger_identity));
+
+    let result = instance
+        .methods()
+        .initialize(stranger_target)
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::NotProxyOwner(stranger_identity));
+
+    // Nothing was written
+    let proxy_target = instance
+        .methods()
+        ._proxy_target()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(proxy_target, None);
+}
+
+#[tokio::test]
+async fn test_contract_without_configured_owner_is_not_upgradable() {
+    let (_instance, _id, owner, non_owner) = get_contract_instance().await;
+
+    // Deployed without an owner, nobody can claim the contract
+    let id = Contract::load_from(
+        "contracts/strategy/out/debug/strategy.bin",
+        LoadConfiguration::default().with_salt([2u8; 32]),
+    )
+    .unwrap()
+    .deploy(&owner, TxPolicies::default())
+    .await
+    .unwrap();
+
+    for wallet in [owner, non_owner] {
+        let result = Strategy::new(id.clone(), wallet.clone())
+            .methods()
+            ._set_proxy_target(ContractId::from([9u8; 32]))
+            .call()
+            .await;
+        assert_reverted_with(
+            result,
+            StrategyError::NotProxyOwner(Identity::Address(wallet.address().into())),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_upgrade_emits_event_and_reports_version() {
+    let (instance, _id, _owner, _non_owner) = get_contract_instance().await;
+    let initial_target = ContractId::from([1u8; 32]);
+    let new_target = ContractId::from([2u8; 32]);
+
+    let version = instance
+        .methods()
+        .implementation_version()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(version, 2);
+
+    instance
+        .methods()
+        .initialize(initial_target)
+        .call()
+        .await
+        .unwrap();
+
+    let response = instance
+        .methods()
+        ._set_proxy_target(new_target)
+        .call()
+        .await
+        .unwrap();
+
+    let events = response.decode_logs_with_type::<Upgraded>().unwrap();
+    assert_eq!(
+        events,
+        vec![Upgraded {
+            old_target: Some(initial_target),
+            new_target,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_proxy_events() {
+    let (instance, _id, owner, _non_owner) = get_contract_instance().await;
+    let initial_target = ContractId::from([1u8; 32]);
+    let new_target = ContractId::from([2u8; 32]);
+    let owner_state = State::Initialized(Identity::Address(owner.address().into()));
+
+    let response = instance
+        .methods()
+        .initialize(initial_target)
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.decode_logs_with_type::<ProxyOwnerSet>().unwrap(),
+        vec![ProxyOwnerSet { new_proxy_owner: owner_state.clone() }]
+    );
+    assert_eq!(
+        response.decode_logs_with_type::<ProxyTargetSet>().unwrap(),
+        vec![ProxyTargetSet { new_target: initial_target }]
+    );
+
+    let response = instance
+        .methods()
+        ._set_proxy_target(new_target)
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.decode_logs_with_type::<ProxyTargetSet>().unwrap(),
+        vec![ProxyTargetSet { new_target }]
+    );
+}
+
+#[tokio::test]
+async fn test_renounce_upgradability() {
+    let (instance, _id, _owner, _non_owner) = get_contract_instance().await;
+
+    let response = instance
+        .methods()
+        .renounce_upgradability()
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.decode_logs_with_type::<ProxyOwnerSet>().unwrap(),
+        vec![ProxyOwnerSet { new_proxy_owner: State::Revoked }]
+    );
+
+    let proxy_owner = instance
+        .methods()
+        ._proxy_owner()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(proxy_owner, State::Revoked);
+}
+
+#[tokio::test]
+async fn test_revoked_proxy_rejects_target_change() {
+    let (instance, _id, _owner, _non_owner) = get_contract_instance().await;
+
+    instance
+        .methods()
+        .initialize(ContractId::from([1u8; 32]))
+        .call()
+        .await
+        .unwrap();
+
+    instance
+        .methods()
+        .renounce_upgradability()
+        .call()
+        .await
+        .unwrap();
+
+    // Revoked for good, even for the former owner
+    let result = instance
+        .methods()
+        ._set_proxy_target(ContractId::from([2u8; 32]))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ProxyRevoked);
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use common::{
+    abi_drift::{check_drift, compiled_functions, declared_functions, AbiDrift},
+    storage_layout::read_json,
+};
+use serde_json::{json, Value};
+
+const FUNGIBLE_INTERFACE: &str = "contracts/interfaces/src/fungible_abi.sw";
+const FUNGIBLE_ABIS: &[&str] = &["UpgradableAsset", "FungibleAsset"];
+const SPROUT_TOKEN_INTERFACE: &str = "contracts/interfaces/src/sprout_token.sw";
+
+const INTERFACE: &str = r#"
+abi Token {
+    /*
+        Supply
+    */
+    #[storage(read)]
+    fn total_supply(asset_id: AssetId) -> Option<u64>;
+
+    /// Burns coins sent with the call.
+    #[storage(read, write), payable]
+    fn burn(sub_id: SubId, amount: u64);
+
+    #[storage(read, write)]
+    #[payable]
+    fn deposit(
+        receiver: Identity,
+        route: Vec<(AssetId, u64)>, // swapped in order
+    ) -> u64;
+}
+
+abi Other {
+    fn ignored();
+}
+"#;
+
+// Builds a compiled ABI, using each compiled type string as its own concrete type id
+fn compiled(functions: &[(&str, &[&str], &str, Value)]) -> Value {
+    let mut types: Vec<&str> = functions
+        .iter()
+        .flat_map(|(_, inputs, output, _)| inputs.iter().chain([output]).copied())
+        .collect();
+    types.sort();
+    types.dedup();
+
+    json!({
+        "concreteTypes": types
+            .iter()
+            .map(|ty| json!({ "type": ty, "concreteTypeId": ty }))
+            .collect::<Vec<_>>(),
+        "functions": functions
+            .iter()
+            .map(|(name, inputs, output, attributes)| json!({
+                "name": name,
+                "inputs": inputs
+                    .iter()
+                    .map(|ty| json!({ "name": "arg", "concreteTypeId": ty }))
+                    .collect::<Vec<_>>(),
+                "output": output,
+                "attributes": attributes,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn matching_abi() -> Vec<(&'static str, &'static [&'static str], &'static str, Value)> {
+    vec![
+        (
+            "total_supply",
+            &["struct std::asset_id::AssetId"],
+            "enum std::option::Option<u64>",
+            json!([{ "name": "storage", "arguments": ["read"] }]),
+        ),
+        (
+            "burn",
+            &["b256", "u64"],
+            "()",
+            json!([
+                { "name": "doc-comment", "arguments": [" Burns coins sent with the call."] },
+                { "name": "storage", "arguments": ["write", "read"] },
+                { "name": "payable", "arguments": [] },
+            ]),
+        ),
+        (
+            "deposit",
+            &[
+                "enum std::identity::Identity",
+                "struct std::vec::Vec<(struct std::asset_id::AssetId, u64)>",
+            ],
+            "u64",
+            json!([
+                { "name": "storage", "arguments": ["read", "write"] },
+                { "name": "payable", "arguments": [] },
+            ]),
+        ),
+    ]
+}
+
+#[test]
+fn matching_interface_has_no_drift() {
+    let declared = declared_functions(INTERFACE, &["Token"]);
+    let abi = compiled(&matching_abi());
+
+    assert_eq!(declared.len(), 3);
+    assert!(declared["deposit"].payable);
+    assert_eq!(declared["deposit"].storage, vec!["read", "write"]);
+    assert!(check_drift(&declared, &compiled_functions(&abi)).is_empty());
+}
+
+#[test]
+fn missing_extra_and_changed_functions_are_reported() {
+    let declared = declared_functions(INTERFACE, &["Token"]);
+    let abi = compiled(&[
+        (
+            "burn",
+            &["b256", "u64"],
+            "()",
+            json!([{ "name": "storage", "arguments": ["read", "write"] }]),
+        ),
+        ("transfer", &[], "()", Value::Null),
+    ]);
+
+    assert_eq!(
+        check_drift(&declared, &compiled_functions(&abi)),
+        vec![
+            AbiDrift::Changed { name: "burn".into() },
+            AbiDrift::NotImplemented { name: "deposit".into() },
+            AbiDrift::NotImplemented { name: "total_supply".into() },
+            AbiDrift::Undeclared { name: "transfer".into() },
+        ]
+    );
+}
+
+#[test]
+fn changed_input_and_output_types_are_reported() {
+    let declared = declared_functions(INTERFACE, &["Token"]);
+    let mut abi = matching_abi();
+    // Same number of inputs, different type
+    abi[1].1 = &["b256", "u32"];
+    // Same inputs, different output
+    abi[2].2 = "u32";
+
+    assert_eq!(
+        check_drift(&declared, &compiled_functions(&compiled(&abi))),
+        vec![
+            AbiDrift::Changed { name: "burn".into() },
+            AbiDrift::Changed { name: "deposit".into() },
+        ]
+    );
+}
+
+#[test]
+fn fungible_contract_matches_interface() {
+    let source = fs::read_to_string(FUNGIBLE_INTERFACE).unwrap();
+    let declared = declared_functions(&source, FUNGIBLE_ABIS);
+    let abi = read_json(Path::new("contracts/fungible/out/debug/fungible-abi.json"));
+
+    let drift = check_drift(&declared, &compiled_functions(&abi));
+    assert!(
+        drift.is_empty(),
+        "fungible contract has drifted from {FUNGIBLE_INTERFACE}: {drift:?}"
+    );
+}
+
+#[test]
+fn sprout_token_contract_matches_interface() {
+    let source = fs::read_to_string(SPROUT_TOKEN_INTERFACE).unwrap();
+    let declared = declared_functions(&source, &["Sprout_Token"]);
+    let abi = read_json(Path::new("contracts/sprout-token/out/debug/sprout-token-abi.json"));
+
+    let drift = check_drift(&declared, &compiled_functions(&abi));
+    assert!(
+        drift.is_empty(),
+        "sprout token contract has drifted from {SPROUT_TOKEN_INTERFACE}: {drift:?}"
+    );
+}
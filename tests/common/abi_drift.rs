@@ -0,0 +1,276 @@
+//! Compares the functions declared by a Sway `abi` block with the functions a
+//! compiled contract exposes, so the interface library and the contracts that
+//! implement it can't silently drift apart.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// The parts of a function that callers of an ABI depend on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiFunction {
+    /// Input types in order, see `normalize_type`.
+    pub inputs: Vec<String>,
+    /// Output type, `()` when the function returns nothing.
+    pub output: String,
+    /// Storage access, sorted, e.g. `["read", "write"]`.
+    pub storage: Vec<String>,
+    /// Whether the function accepts coins.
+    pub payable: bool,
+}
+
+/// A difference between an interface and a compiled implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiDrift {
+    /// Declared by the interface but not exposed by the compiled contract.
+    NotImplemented { name: String },
+    /// Exposed by the compiled contract but not declared by the interface.
+    Undeclared { name: String },
+    /// Present in both with different inputs, output, storage access or payability.
+    Changed { name: String },
+}
+
+/// Collects the functions of the named `abi` blocks in a Sway source file.
+pub fn declared_functions(source: &str, abi_names: &[&str]) -> BTreeMap<String, AbiFunction> {
+    let mut functions = BTreeMap::new();
+    let mut in_abi = false;
+    let mut in_comment = false;
+    let mut storage = Vec::new();
+    let mut payable = false;
+    // A signature can span several lines, it ends at the `;`
+    let mut signature: Option<String> = None;
+
+    for line in source.lines().map(str::trim) {
+        if in_comment {
+            in_comment = !line.contains("*/");
+            continue;
+        }
+        if line.starts_with("/*") {
+            in_comment = !line.contains("*/");
+            continue;
+        }
+        let line = line.split("//").next().unwrap_or_default().trim();
+
+        if let Some(partial) = signature.as_mut() {
+            partial.push(' ');
+            partial.push_str(line);
+        } else if let Some(name) = line.strip_prefix("abi ") {
+            let name = name.trim_end_matches('{').trim();
+            in_abi = abi_names.contains(&name);
+            continue;
+        } else if !in_abi {
+            continue;
+        } else if line == "}" {
+            in_abi = false;
+            continue;
+        } else if let Some(attributes) = line.strip_prefix("#[") {
+            // Attributes may be split over several `#[...]` lines
+            let attributes = attributes.trim_end_matches(']');
+            if let Some(access) = attributes
+                .strip_prefix("storage(")
+                .and_then(|rest| rest.split(')').next())
+            {
+                storage.extend(access.split(',').map(|a| a.trim().to_string()));
+                storage.sort();
+                storage.dedup();
+            }
+            payable |= attributes.contains("payable");
+            continue;
+        } else if line.starts_with("fn ") {
+            signature = Some(line.to_string());
+        }
+
+        let Some(complete) = signature.take_if(|partial| partial.contains(';')) else {
+            continue;
+        };
+        let (name, function) = parse_signature(&complete);
+        functions.insert(
+            name,
+            AbiFunction {
+                storage: std::mem::take(&mut storage),
+                payable: std::mem::take(&mut payable),
+                ..function
+            },
+        );
+    }
+
+    functions
+}
+
+/// Parses `fn name(arg: Type, ...) -> Output;` into the name and its types.
+fn parse_signature(signature: &str) -> (String, AbiFunction) {
+    let signature = signature.trim_start_matches("fn ").trim_end_matches(';');
+    let (name, rest) = signature.split_once('(').expect("function inputs");
+    let close = closing_paren(rest);
+
+    let inputs = split_top_level(&rest[..close])
+        .into_iter()
+        .map(|input| {
+            let (_, ty) = input.split_once(':').expect("input type");
+            normalize_type(ty)
+        })
+        .collect();
+    let output = rest[close + 1..]
+        .trim()
+        .strip_prefix("->")
+        .map_or_else(|| "()".to_string(), normalize_type);
+
+    (
+        name.trim().to_string(),
+        AbiFunction { inputs, output, storage: Vec::new(), payable: false },
+    )
+}
+
+/// Returns the index of the `)` closing the inputs that `rest` starts within.
+fn closing_paren(rest: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' if depth == 0 => return i,
+            ')' | '>' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    panic!("unclosed inputs in `{rest}`")
+}
+
+/// Splits on the commas that aren't nested in a tuple, array or generic.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&list[start..]);
+    parts.into_iter().filter(|part| !part.trim().is_empty()).collect()
+}
+
+/// Reduces a source or compiled type to a comparable form: module paths and
+/// `struct`/`enum` keywords are dropped and std aliases are resolved, so
+/// `SubId` and `b256`, or `Option<u64>` and `enum std::option::Option<u64>`,
+/// compare equal.
+pub fn normalize_type(ty: &str) -> String {
+    let mut normalized = String::new();
+    let mut word = String::new();
+    let mut chars = ty.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if c == ':' && chars.peek() == Some(&':') {
+            // Drop the module path leading up to the type name
+            chars.next();
+            word.clear();
+            continue;
+        }
+        push_word(&mut normalized, &mut word);
+        if !c.is_whitespace() {
+            normalized.push(c);
+        }
+    }
+    push_word(&mut normalized, &mut word);
+
+    normalized
+}
+
+fn push_word(normalized: &mut String, word: &mut String) {
+    match word.as_str() {
+        "" | "struct" | "enum" => {}
+        "SubId" => normalized.push_str("b256"),
+        other => normalized.push_str(other),
+    }
+    word.clear();
+}
+
+/// Collects the functions of a compiled `*-abi.json`.
+pub fn compiled_functions(abi: &Value) -> BTreeMap<String, AbiFunction> {
+    let types: BTreeMap<&str, String> = abi["concreteTypes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|ty| {
+            (
+                ty["concreteTypeId"].as_str().expect("concrete type id"),
+                normalize_type(ty["type"].as_str().expect("concrete type")),
+            )
+        })
+        .collect();
+    let type_of = |id: &Value| {
+        let id = id.as_str().expect("concrete type id");
+        types.get(id).cloned().unwrap_or_else(|| panic!("unknown concrete type {id}"))
+    };
+
+    abi["functions"]
+        .as_array()
+        .expect("abi functions should be an array")
+        .iter()
+        .map(|function| {
+            let name = function["name"].as_str().expect("function name").to_string();
+            let inputs = function["inputs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|input| type_of(&input["concreteTypeId"]))
+                .collect();
+            let output = type_of(&function["output"]);
+
+            let mut storage = Vec::new();
+            let mut payable = false;
+            for attribute in function["attributes"].as_array().into_iter().flatten() {
+                match attribute["name"].as_str() {
+                    Some("storage") => {
+                        storage = attribute["arguments"]
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|a| a.as_str().map(str::to_string))
+                            .collect();
+                        storage.sort();
+                    }
+                    Some("payable") => payable = true,
+                    _ => {}
+                }
+            }
+
+            (name, AbiFunction { inputs, output, storage, payable })
+        })
+        .collect()
+}
+
+/// Returns every difference between the interface and the compiled contract.
+pub fn check_drift(
+    declared: &BTreeMap<String, AbiFunction>,
+    compiled: &BTreeMap<String, AbiFunction>,
+) -> Vec<AbiDrift> {
+    let mut drift = Vec::new();
+
+    for (name, function) in declared {
+        match compiled.get(name) {
+            None => drift.push(AbiDrift::NotImplemented { name: name.clone() }),
+            Some(compiled) if compiled != function => {
+                drift.push(AbiDrift::Changed { name: name.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+    drift.extend(
+        compiled
+            .keys()
+            .filter(|name| !declared.contains_key(*name))
+            .map(|name| AbiDrift::Undeclared { name: name.clone() }),
+    );
+
+    drift
+}
@@ -0,0 +1,8 @@
+//! Helpers shared between the integration test crates.
+
+#![allow(dead_code)]
+
+pub mod abi_drift;
+pub mod revert;
+pub mod storage_layout;
+pub mod time;
@@ -0,0 +1,35 @@
+//! Assertions on the typed errors a contract call reverted with.
+
+use std::fmt::Debug;
+
+use fuels::types::errors::{transaction::Reason, Error, Result};
+
+/// Asserts that `result` failed because the contract reverted with `expected`.
+///
+/// Contracts log their error enum before reverting, and the SDK decodes that
+/// log into the revert reason using the type's `Debug` output, so comparing
+/// against `format!("{expected:?}")` checks the exact variant and payload.
+pub fn assert_reverted_with<T: Debug, E: Debug>(result: Result<T>, expected: E) {
+    let expected = format!("{expected:?}");
+
+    match result {
+        Ok(value) => panic!("expected revert with `{expected}`, but the call returned {value:?}"),
+        Err(Error::Transaction(Reason::Reverted { reason, .. })) => {
+            assert_eq!(reason, expected, "call reverted with an unexpected error")
+        }
+        Err(err) => panic!("expected revert with `{expected}`, got {err:?}"),
+    }
+}
+
+/// Like [`assert_reverted_with`], for calls whose error payload is not known
+/// up front; only the variant name is checked.
+pub fn assert_reverted_with_variant<T: Debug>(result: Result<T>, variant: &str) {
+    match result {
+        Ok(value) => panic!("expected revert with `{variant}`, but the call returned {value:?}"),
+        Err(Error::Transaction(Reason::Reverted { reason, .. })) => assert!(
+            reason == variant || reason.starts_with(&format!("{variant}(")),
+            "expected revert with `{variant}`, got `{reason}`"
+        ),
+        Err(err) => panic!("expected revert with `{variant}`, got {err:?}"),
+    }
+}
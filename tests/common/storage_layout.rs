@@ -0,0 +1,313 @@
+//! Compares the compiled layout of a contract with a committed baseline of the
+//! deployed implementation and flags changes that would break a proxy upgrade
+//! from one to the other.
+//!
+//! The storage slots come from `forc build`'s `*-storage_slots.json` and the
+//! function signatures from `*-abi.json`. Neither says what type a slot holds,
+//! nor lists fields like `StorageMap`s that only get slots once written, so the
+//! fields of the source's `storage` block are read as well and matched to the
+//! compiled slots by their key. The baseline is a JSON snapshot of the same,
+//! see `ContractLayout::to_json`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::abi_drift::{compiled_functions, normalize_type, AbiFunction};
+
+/// A change between two implementations that is unsafe to upgrade across.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutIssue {
+    /// A storage slot of the old implementation is not declared by the new one,
+    /// so the data written there would be orphaned. Named by the old field when
+    /// its source declares one, by the raw key otherwise.
+    MissingSlot { field: String },
+    /// A storage field kept its key but changed type, so the new implementation
+    /// would read the old data with a different size or encoding.
+    ChangedSlot { field: String, old: String, new: String },
+    /// A function of the old implementation no longer exists.
+    MissingFunction { name: String },
+    /// A function kept its name but its inputs, output, storage access or
+    /// payability changed.
+    ChangedFunction { name: String },
+}
+
+/// A field of a contract's `storage` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageField {
+    /// The slot key the field is stored under, as hex without `0x`.
+    pub key: String,
+    /// The type stored there, see `normalize_type`.
+    pub ty: String,
+}
+
+/// The parts of a contract that matter for upgrades.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractLayout {
+    /// The `IMPLEMENTATION_VERSION` the contract was built with.
+    pub version: u64,
+    /// Compiled storage slot keys.
+    pub slots: BTreeSet<String>,
+    /// Field path, e.g. `strategy.owner`, to where it is stored.
+    pub fields: BTreeMap<String, StorageField>,
+    /// Function name to its signature.
+    pub functions: BTreeMap<String, AbiFunction>,
+}
+
+impl ContractLayout {
+    /// Loads `<name>-storage_slots.json` and `<name>-abi.json` from a forc
+    /// output directory, typing the slots from the contract's `source`.
+    pub fn load(out_dir: impl AsRef<Path>, name: &str, source: &str) -> Self {
+        let out_dir = out_dir.as_ref();
+        let slots = read_json(&out_dir.join(format!("{name}-storage_slots.json")));
+        let abi = read_json(&out_dir.join(format!("{name}-abi.json")));
+
+        Self::from_compiled(source, &slots, &abi)
+    }
+
+    /// Combines a compiled layout with the fields `source` declares.
+    ///
+    /// Panics if the source declares a field the compiler didn't lay out, since
+    /// the two would then disagree on the keys.
+    pub fn from_compiled(source: &str, slots: &Value, abi: &Value) -> Self {
+        let slots: BTreeSet<String> = slots
+            .as_array()
+            .expect("storage slots should be an array")
+            .iter()
+            .map(|slot| slot["key"].as_str().expect("slot key").to_string())
+            .collect();
+        let fields = declared_fields(source);
+
+        for (path, field) in &fields {
+            assert!(
+                is_storage_collection(&field.ty) || slots.contains(&field.key),
+                "`{path}` has no compiled slot at {}",
+                field.key
+            );
+        }
+
+        Self {
+            version: declared_version(source),
+            slots,
+            fields,
+            functions: compiled_functions(abi),
+        }
+    }
+
+    /// Loads a baseline written by `save`.
+    pub fn read(path: impl AsRef<Path>) -> Self {
+        Self::from_json(&read_json(path.as_ref()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(&self.to_json()).unwrap();
+        fs::write(path, contents + "\n")
+            .unwrap_or_else(|err| panic!("failed to write {}: {err}", path.display()));
+    }
+
+    pub fn from_json(layout: &Value) -> Self {
+        let strings = |value: &Value| -> Vec<String> {
+            value
+                .as_array()
+                .expect("expected an array")
+                .iter()
+                .map(|item| item.as_str().expect("expected a string").to_string())
+                .collect()
+        };
+
+        let fields = layout["fields"]
+            .as_object()
+            .expect("layout fields should be an object")
+            .iter()
+            .map(|(path, field)| {
+                let field = StorageField {
+                    key: field["key"].as_str().expect("field key").to_string(),
+                    ty: field["type"].as_str().expect("field type").to_string(),
+                };
+                (path.clone(), field)
+            })
+            .collect();
+
+        let functions = layout["functions"]
+            .as_object()
+            .expect("layout functions should be an object")
+            .iter()
+            .map(|(name, function)| {
+                let function = AbiFunction {
+                    inputs: strings(&function["inputs"]),
+                    output: function["output"].as_str().expect("function output").to_string(),
+                    storage: strings(&function["storage"]),
+                    payable: function["payable"].as_bool().expect("function payability"),
+                };
+                (name.clone(), function)
+            })
+            .collect();
+
+        Self {
+            version: layout["version"].as_u64().expect("layout version"),
+            slots: strings(&layout["slots"]).into_iter().collect(),
+            fields,
+            functions,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let fields: serde_json::Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(path, field)| (path.clone(), json!({ "key": field.key, "type": field.ty })))
+            .collect();
+        let functions: serde_json::Map<String, Value> = self
+            .functions
+            .iter()
+            .map(|(name, function)| {
+                let function = json!({
+                    "inputs": function.inputs,
+                    "output": function.output,
+                    "storage": function.storage,
+                    "payable": function.payable,
+                });
+                (name.clone(), function)
+            })
+            .collect();
+
+        json!({
+            "version": self.version,
+            "slots": self.slots,
+            "fields": fields,
+            "functions": functions,
+        })
+    }
+
+    /// The field stored at `key`, if the source declares one.
+    fn field_at(&self, key: &str) -> Option<(&String, &StorageField)> {
+        self.fields.iter().find(|(_, field)| field.key == key)
+    }
+}
+
+/// Returns every issue that makes upgrading from `old` to `new` unsafe.
+///
+/// New slots and new functions are fine, as are changed slot defaults since
+/// they only apply to fresh deployments.
+pub fn check_compatibility(old: &ContractLayout, new: &ContractLayout) -> Vec<LayoutIssue> {
+    let mut issues = Vec::new();
+
+    for key in &old.slots {
+        if !new.slots.contains(key) {
+            let field = old.field_at(key).map_or_else(|| key.clone(), |(path, _)| path.clone());
+            issues.push(LayoutIssue::MissingSlot { field });
+        }
+    }
+
+    for (path, old_field) in &old.fields {
+        match new.field_at(&old_field.key) {
+            // Fields with a compiled slot were covered above
+            None if !old.slots.contains(&old_field.key) => {
+                issues.push(LayoutIssue::MissingSlot { field: path.clone() })
+            }
+            Some((_, new_field)) if new_field.ty != old_field.ty => {
+                issues.push(LayoutIssue::ChangedSlot {
+                    field: path.clone(),
+                    old: old_field.ty.clone(),
+                    new: new_field.ty.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    for (name, old_function) in &old.functions {
+        match new.functions.get(name) {
+            None => issues.push(LayoutIssue::MissingFunction { name: name.clone() }),
+            Some(new_function) if new_function != old_function => {
+                issues.push(LayoutIssue::ChangedFunction { name: name.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+
+    issues
+}
+
+/// The key forc stores a field at when it isn't declared with `in`: the
+/// SHA-256 of its path, e.g. `storage::strategy.owner` or `storage.owner`.
+pub fn field_key(namespaces: &[&str], name: &str) -> String {
+    let path = match namespaces {
+        [] => format!("storage.{name}"),
+        _ => format!("storage::{}.{name}", namespaces.join("::")),
+    };
+
+    Sha256::digest(path.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Collects the fields of the `storage` block, keyed by `namespace.field`.
+pub fn declared_fields(source: &str) -> BTreeMap<String, StorageField> {
+    let mut fields = BTreeMap::new();
+    let mut in_storage = false;
+    let mut namespaces: Vec<&str> = Vec::new();
+
+    for line in source.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+
+        if !in_storage {
+            in_storage = line == "storage {";
+            continue;
+        }
+        if line.starts_with('}') {
+            if namespaces.pop().is_none() {
+                break;
+            }
+            continue;
+        }
+        if let Some(namespace) = line.strip_suffix('{').filter(|rest| !rest.contains(':')) {
+            namespaces.push(namespace.trim());
+            continue;
+        }
+
+        let Some((declaration, _default)) = line.split_once(" = ") else {
+            continue;
+        };
+        let (name, ty) = declaration.split_once(':').expect("storage field type");
+        let (name, key) = match name.split_once(" in ") {
+            Some((name, key)) => (name.trim(), key.trim().trim_start_matches("0x").to_string()),
+            None => (name.trim(), field_key(&namespaces, name.trim())),
+        };
+        let path = namespaces.iter().copied().chain([name]).collect::<Vec<_>>().join(".");
+
+        fields.insert(path, StorageField { key, ty: normalize_type(ty) });
+    }
+
+    fields
+}
+
+/// Whether a field of this type is laid out lazily by its methods rather than
+/// by the compiler, like a `StorageMap`.
+fn is_storage_collection(ty: &str) -> bool {
+    ["StorageMap<", "StorageVec<", "StorageString", "StorageBytes"]
+        .iter()
+        .any(|collection| ty.starts_with(collection))
+}
+
+fn declared_version(source: &str) -> u64 {
+    source
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("const IMPLEMENTATION_VERSION: u64 = "))
+        .and_then(|version| version.trim_end_matches(';').parse().ok())
+        .expect("IMPLEMENTATION_VERSION should be declared")
+}
+
+pub fn read_json(path: &Path) -> Value {
+    let contents = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display()))
+}
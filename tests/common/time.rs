@@ -0,0 +1,22 @@
+//! Block time helpers for contracts that read `std::block::timestamp`.
+
+use fuels::prelude::Provider;
+
+/// Offset between Unix seconds and the TAI64 label `timestamp()` returns.
+pub const TAI64_OFFSET: u64 = (1 << 62) + 10;
+
+/// The latest block time as a contract sees it, in TAI64 seconds.
+pub async fn block_timestamp(provider: &Provider) -> u64 {
+    let time = provider.latest_block_time().await.unwrap().unwrap();
+    time.timestamp() as u64 + TAI64_OFFSET
+}
+
+/// Produces a block `seconds` after the latest one, so later calls run at
+/// least that far in the future.
+pub async fn advance_time(provider: &Provider, seconds: u64) {
+    let time = provider.latest_block_time().await.unwrap().unwrap();
+    provider
+        .produce_blocks(1, Some(time + chrono::Duration::seconds(seconds as i64)))
+        .await
+        .unwrap();
+}
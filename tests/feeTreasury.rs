@@ -1,16 +1,30 @@
 use fuels::{
     prelude::*,
-    types::{Identity, ContractId, Address},
+    types::{Identity, ContractId, Address, Bits256},
 };
 
  use fuels::tx::TxParameters;
 
+mod common;
+use common::revert::assert_reverted_with;
+use common::time::{advance_time, block_timestamp};
+use abigen_bindings::treasury_contract_mod::Error as TreasuryError;
+
  
 
-abigen!(Contract(
-    name = "TreasuryContract",
-    abi = "contracts/treasury/out/debug/treasury-abi.json"
-));
+abigen!(
+    Contract(
+        name = "TreasuryContract",
+        abi = "contracts/treasury/out/debug/treasury-abi.json"
+    ),
+    Contract(
+        name = "StrategyContract",
+        abi = "contracts/strategy/out/debug/strategy-abi.json"
+    ),
+    Contract(
+        name = "MiraAMM",
+        abi = "contracts/mockMira/out/debug/mockMira-abi.json"
+    ));
 
 const BASE_ASSET_ID: [u8; 32] = [0u8; 32];
 
@@ -25,9 +39,13 @@ async fn get_contract_instance() -> (TreasuryContract<WalletUnlocked>, ContractI
     
     let wallet = wallets.get(0).unwrap().clone();
     
+    // Only the deployer may construct the treasury
+    let configurables = TreasuryContractConfigurables::default()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(wallet.address().into())))
+        .unwrap();
     let id = Contract::load_from(
         "contracts/treasury/out/debug/treasury.bin",
-        LoadConfiguration::default(),
+        LoadConfiguration::default().with_configurables(configurables),
     )
     .unwrap()
     .deploy(&wallet, TxPolicies::default())
@@ -63,9 +81,13 @@ async fn get_contract_instance_with_mock_strategy() -> (TreasuryContract<WalletU
     // Create a mock strategy ID for testing
     let mock_strategy_id = ContractId::from([2u8; 32]);
     
+    // Only the deployer may construct the treasury
+    let configurables = TreasuryContractConfigurables::default()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(wallet.address().into())))
+        .unwrap();
     let id = Contract::load_from(
         "contracts/treasury/out/debug/treasury.bin",
-        LoadConfiguration::default(),
+        LoadConfiguration::default().with_configurables(configurables),
     )
     .unwrap()
     .deploy(&wallet, TxPolicies::default())
@@ -110,7 +132,7 @@ async fn test_owner_initialization() {
         .call()
         .await;
     
-    assert!(result.is_err());
+    assert_reverted_with(result, TreasuryError::OwnerAlreadyInitialized);
 }
 
 #[tokio::test]
@@ -118,53 +140,83 @@ async fn test_strategy_initialization() {
     let (instance, _id, wallets) = get_contract_instance().await;
     let wallet_0 = wallets.get(0).unwrap().clone();
 
-    // Verify initial strategy is set correctly
-    let strategy = instance
+    // The strategy passed to the constructor should be authorized
+    let expected_strategy = Identity::Address(wallet_0.address().into());
+    let is_authorized = instance
         .methods()
-        .get_strategy()
+        .is_strategy_authorized(expected_strategy)
         .call()
         .await
         .unwrap()
         .value;
-    
-    // The strategy should be the wallet's address
-    let expected_strategy = Identity::Address(wallet_0.address().into());
-    assert_eq!(strategy, expected_strategy);
+
+    assert!(is_authorized);
 }
 
 #[tokio::test]
 async fn test_strategy_initialization_with_mock() {
-    let (instance, _id, _wallets) = get_contract_instance_with_mock_strategy().await;
+    let (instance, _id, wallets) = get_contract_instance_with_mock_strategy().await;
+    let wallet_0 = wallets.get(0).unwrap().clone();
 
-    // Verify initial strategy is set correctly
-    let strategy = instance
+    // The mock strategy we set in get_contract_instance_with_mock_strategy
+    let expected_strategy = Identity::ContractId(ContractId::from([2u8; 32]));
+    let is_authorized = instance
         .methods()
-        .get_strategy()
+        .is_strategy_authorized(expected_strategy)
         .call()
         .await
         .unwrap()
         .value;
-    
-    // The mock strategy we set in get_contract_instance_with_mock_strategy
-    let expected_strategy = Identity::ContractId(ContractId::from([2u8; 32]));
-    assert_eq!(strategy, expected_strategy);
+    assert!(is_authorized);
+
+    // The deployer wallet is only the owner, not a strategy
+    let is_authorized = instance
+        .methods()
+        .is_strategy_authorized(Identity::Address(wallet_0.address().into()))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert!(!is_authorized);
 }
 
 #[tokio::test]
 async fn test_constructor() {
     println!("\n=== Testing treasury constructor ===");
-    let (instance, _, _) = get_contract_instance().await;
-    
-    let owner_address = Address::from([1u8; 32]);
+    let (instance, _, wallets) = get_contract_instance().await;
+    let wallet_0 = wallets.get(0).unwrap().clone();
+    let stranger = wallets.get(1).unwrap().clone();
+
+    // A stranger can't take over an initialized treasury
     let strategy_id = Identity::ContractId(ContractId::from([3u8; 32])); // Different mock strategy
-    
-    let result = instance.methods()
-        .constructor(owner_address, strategy_id)
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .constructor(stranger.address(), strategy_id)
         .call()
         .await;
-        
-    assert!(result.is_ok());
-    println!("✓ Constructor successfully initialized");
+    assert_reverted_with(result, TreasuryError::AlreadyInitialized);
+
+    // Not even the owner can run it twice
+    let result = instance
+        .methods()
+        .constructor(Address::from([1u8; 32]), strategy_id)
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::AlreadyInitialized);
+
+    let owner = instance.methods().get_owner().call().await.unwrap().value;
+    assert_eq!(owner, Identity::Address(wallet_0.address().into()));
+    let is_authorized = instance
+        .methods()
+        .is_strategy_authorized(strategy_id)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert!(!is_authorized);
+    println!("✓ Constructor cannot be called again");
 }
 
 #[tokio::test]
@@ -190,7 +242,10 @@ async fn test_receive_fees_unauthorized() {
         .call()
         .await;
         
-    assert!(result.is_err());
+    assert_reverted_with(
+        result,
+        TreasuryError::Unauthorized(Identity::Address(wallet.address().into())),
+    );
     println!("✓ Unauthorized fee reception correctly rejected");
 }
 
@@ -217,7 +272,7 @@ async fn test_receive_fees_wrong_asset() {
         .call()
         .await;
         
-    assert!(result.is_err());
+    assert_reverted_with(result, TreasuryError::InvalidDepositAsset(wrong_asset));
     println!("✓ Wrong asset fee reception correctly rejected");
 }
 
@@ -243,7 +298,7 @@ async fn test_receive_fees_zero_amount() {
         .call()
         .await;
         
-    assert!(result.is_err());
+    assert_reverted_with(result, TreasuryError::InvalidAmount);
     println!("✓ Zero amount fee reception correctly rejected");
 }
 
@@ -262,7 +317,10 @@ async fn test_withdraw_fees_unauthorized() {
         .call()
         .await;
         
-    assert!(result.is_err());
+    assert_reverted_with(
+        result,
+        TreasuryError::Unauthorized(Identity::Address(wallet.address().into())),
+    );
     println!("✓ Unauthorized fee withdrawal correctly rejected");
 }
 
@@ -295,55 +353,144 @@ async fn test_receive_fees_successful() {
 }
 
 #[tokio::test]
-async fn test_set_strategy() {
+async fn test_add_and_remove_strategy() {
     let (instance, _id, wallets) = get_contract_instance().await;
     let wallet_0 = wallets.get(0).unwrap().clone();
     
-    // Create a new strategy ID
-    let new_strategy_id = ContractId::from([5u8; 32]);
-    let new_strategy_identity = Identity::ContractId(new_strategy_id);
-    
-    // Set new strategy as the owner
+    // Register a second strategy
+    let new_strategy_identity = Identity::ContractId(ContractId::from([5u8; 32]));
     let result = instance.clone()
         .with_account(wallet_0.clone())
         .methods()
-        .set_strategy(new_strategy_identity)
+        .add_strategy(new_strategy_identity)
         .call()
         .await;
         
     assert!(result.is_ok());
     
-    // Verify the strategy was updated
-    let updated_strategy = instance
+    let is_authorized = instance.clone()
         .methods()
-        .get_strategy()
+        .is_strategy_authorized(new_strategy_identity)
         .call()
         .await
         .unwrap()
         .value;
-        
-    assert_eq!(updated_strategy, new_strategy_identity);
+    assert!(is_authorized);
+
+    // Adding the same strategy twice should fail
+    let result = instance.clone()
+        .with_account(wallet_0.clone())
+        .methods()
+        .add_strategy(new_strategy_identity)
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::StrategyAlreadyAuthorized(new_strategy_identity));
+
+    // Remove it again
+    instance.clone()
+        .with_account(wallet_0.clone())
+        .methods()
+        .remove_strategy(new_strategy_identity)
+        .call()
+        .await
+        .unwrap();
+
+    let is_authorized = instance
+        .methods()
+        .is_strategy_authorized(new_strategy_identity)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert!(!is_authorized);
 }
 
 #[tokio::test]
-async fn test_only_owner_can_set_strategy() {
+async fn test_only_owner_can_add_strategy() {
     let (instance, _id, wallets) = get_contract_instance().await;
     let non_owner = wallets.get(1).unwrap().clone();
+    let non_owner_identity = Identity::Address(non_owner.address().into());
     
-    // Non-owner trying to set strategy
-    let new_strategy_id = ContractId::from([6u8; 32]);
-    let new_strategy_identity = Identity::ContractId(new_strategy_id);
+    // Non-owner trying to register a strategy
+    let new_strategy_identity = Identity::ContractId(ContractId::from([6u8; 32]));
     
     let result = instance
         .with_account(non_owner)
         .methods()
-        .set_strategy(new_strategy_identity)
+        .add_strategy(new_strategy_identity)
         .call()
         .await;
     
     // Should fail because sender is not the owner
-    assert!(result.is_err());
-    println!("✓ Non-owner cannot set strategy");
+    assert_reverted_with(result, TreasuryError::Unauthorized(non_owner_identity));
+    println!("✓ Non-owner cannot add strategy");
+}
+
+#[tokio::test]
+async fn test_fees_attributed_per_strategy() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+    let wallet_0 = wallets.get(0).unwrap().clone();
+    let wallet_1 = wallets.get(1).unwrap().clone();
+    let strategy_0 = Identity::Address(wallet_0.address().into());
+    let strategy_1 = Identity::Address(wallet_1.address().into());
+
+    // Register the second wallet as another strategy
+    instance.clone()
+        .with_account(wallet_0.clone())
+        .methods()
+        .add_strategy(strategy_1)
+        .call()
+        .await
+        .unwrap();
+
+    // Both strategies send fees
+    for (wallet, amount) in [(wallet_0.clone(), 1_000), (wallet_1.clone(), 2_500)] {
+        instance.clone()
+            .with_account(wallet)
+            .methods()
+            .receive_fees()
+            .call_params(CallParameters::new(amount, AssetId::from(BASE_ASSET_ID), 1_000_000))
+            .unwrap()
+            .call()
+            .await
+            .unwrap();
+    }
+
+    let fees_0 = instance.clone()
+        .methods()
+        .get_strategy_fees(strategy_0)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    let fees_1 = instance.clone()
+        .methods()
+        .get_strategy_fees(strategy_1)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(fees_0, 1_000);
+    assert_eq!(fees_1, 2_500);
+
+    // A removed strategy can no longer send fees
+    instance.clone()
+        .with_account(wallet_0.clone())
+        .methods()
+        .remove_strategy(strategy_1)
+        .call()
+        .await
+        .unwrap();
+
+    let result = instance
+        .with_account(wallet_1)
+        .methods()
+        .receive_fees()
+        .call_params(CallParameters::new(100, AssetId::from(BASE_ASSET_ID), 1_000_000))
+        .unwrap()
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::Unauthorized(strategy_1));
 }
 
 #[tokio::test]
@@ -376,35 +523,24 @@ async fn can_set_owner() {
 #[tokio::test]
 async fn test_withdraw_fees_successful() {
     println!("\n=== Testing successful fee withdrawal ===");
-    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let (instance, _treasury_id, wallets) = get_contract_instance().await;
     let wallet = wallets.get(0).unwrap().clone();
     
-    // Print the strategy identity for debugging
-    let strategy_identity = instance
-        .methods()
-        .get_strategy()
-        .call()
-        .await
-        .unwrap()
-        .value;
-    println!("✓ Strategy identity: {:?}", strategy_identity);
-    
     // Print the sender identity for debugging
     let wallet_identity = Identity::Address(wallet.address().into());
     println!("✓ Wallet identity: {:?}", wallet_identity);
     
-    // First fund the treasury
+    // First send fees to the treasury as the strategy
     let treasury_funds = 10_000;
-    let treasury_id_bech32 = Bech32ContractId::from(treasury_id);
-    
-    wallet.force_transfer_to_contract(
-        &treasury_id_bech32,
-        treasury_funds,
-        AssetId::from(BASE_ASSET_ID),
-        TxPolicies::default()
-    )
-    .await
-    .unwrap();
+    instance.clone()
+        .with_account(wallet.clone())
+        .methods()
+        .receive_fees()
+        .call_params(CallParameters::new(treasury_funds, AssetId::from(BASE_ASSET_ID), 1_000_000))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
     
     println!("✓ Funded treasury with {} tokens", treasury_funds);
     
@@ -446,18 +582,841 @@ async fn test_withdraw_fees_successful() {
 }
 
 #[tokio::test]
-#[should_panic]
 async fn only_owner_can_set_owner() {
     let (instance, _id, wallets) = get_contract_instance().await;
     let non_owner = wallets.get(1).unwrap();
     let new_owner = Identity::Address(non_owner.address().into());
     
     // This should fail - non-owner trying to set itself as owner
-    instance
+    let result = instance
         .with_account(non_owner.clone())
         .methods()
         .set_owner(new_owner)
         .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::Unauthorized(new_owner));
+}
+async fn fund_treasury(wallet: &WalletUnlocked, treasury_id: ContractId, amount: u64) {
+    wallet.force_transfer_to_contract(
+        &Bech32ContractId::from(treasury_id),
+        amount,
+        AssetId::from(BASE_ASSET_ID),
+        TxPolicies::default()
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_owner_withdraw_to_recipient() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = wallets.get(2).unwrap().clone();
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let recipient_balance_before = recipient.get_asset_balance(&AssetId::from(BASE_ASSET_ID)).await.unwrap();
+
+    instance
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(AssetId::from(BASE_ASSET_ID), 4_000, Identity::Address(recipient.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let recipient_balance_after = recipient.get_asset_balance(&AssetId::from(BASE_ASSET_ID)).await.unwrap();
+    assert_eq!(recipient_balance_after - recipient_balance_before, 4_000);
+}
+
+#[tokio::test]
+async fn test_withdraw_unauthorized() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let non_owner = wallets.get(1).unwrap().clone();
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let result = instance
+        .with_account(non_owner.clone())
+        .methods()
+        .withdraw(AssetId::from(BASE_ASSET_ID), 1_000, Identity::Address(non_owner.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+
+    assert_reverted_with(
+        result,
+        TreasuryError::Unauthorized(Identity::Address(non_owner.address().into())),
+    );
+    println!("✓ Non-owner cannot withdraw from treasury");
+}
+
+#[tokio::test]
+async fn test_treasurer_can_withdraw() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let treasurer = wallets.get(1).unwrap().clone();
+    let treasurer_identity = Identity::Address(treasurer.address().into());
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .set_treasurer(Some(treasurer_identity))
+        .call()
+        .await
+        .unwrap();
+
+    let current_treasurer = instance.clone()
+        .methods()
+        .get_treasurer()
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(current_treasurer, Some(treasurer_identity));
+
+    let result = instance
+        .with_account(treasurer.clone())
+        .methods()
+        .withdraw(AssetId::from(BASE_ASSET_ID), 1_000, treasurer_identity)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_withdraw_spending_limit() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = Identity::Address(wallets.get(2).unwrap().address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    // Allow at most 5_000 per day
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .set_spending_limit(base_asset, Some(SpendingLimit { max_amount: 5_000, period: 86_400 }))
+        .call()
+        .await
+        .unwrap();
+
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 3_000, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    // This would take the period total to 5_500
+    let result = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 2_500, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::SpendingLimitExceeded(2_000));
+    println!("✓ Withdrawal over the period limit correctly rejected");
+
+    // Exactly reaching the limit is fine
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 2_000, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let spent = instance.clone()
+        .methods()
+        .get_spent_in_period(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(spent, 5_000);
+
+    // Removing the limit lifts the restriction
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .set_spending_limit(base_asset, None)
+        .call()
+        .await
+        .unwrap();
+
+    let result = instance
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 1_000, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_spending_limit_rolls_over_after_period() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = Identity::Address(wallets.get(2).unwrap().address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    fund_treasury(&owner, treasury_id, 20_000).await;
+
+    // Allow at most 5_000 per hour
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .set_spending_limit(base_asset, Some(SpendingLimit { max_amount: 5_000, period: 3_600 }))
+        .call()
         .await
         .unwrap();
-}
\ No newline at end of file
+
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 5_000, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let result = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 1, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::SpendingLimitExceeded(0));
+
+    // Once the period has elapsed the full limit is available again
+    advance_time(owner.try_provider().unwrap(), 3_601).await;
+
+    let spent = instance.clone()
+        .methods()
+        .get_spent_in_period(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(spent, 0);
+
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 5_000, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let spent = instance.clone()
+        .methods()
+        .get_spent_in_period(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(spent, 5_000);
+
+    let result = instance
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 1, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::SpendingLimitExceeded(0));
+}
+
+#[tokio::test]
+async fn test_only_owner_can_set_spending_limit() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+    let non_owner = wallets.get(1).unwrap().clone();
+    let non_owner_identity = Identity::Address(non_owner.address().into());
+
+    let result = instance
+        .with_account(non_owner)
+        .methods()
+        .set_spending_limit(AssetId::from(BASE_ASSET_ID), Some(SpendingLimit { max_amount: 1, period: 1 }))
+        .call()
+        .await;
+
+    assert_reverted_with(result, TreasuryError::Unauthorized(non_owner_identity));
+}
+
+#[tokio::test]
+async fn test_stream_fully_vested_claim() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let contributor = wallets.get(2).unwrap().clone();
+    let contributor_identity = Identity::Address(contributor.address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    // A schedule entirely in the past has fully vested
+    let stream_id = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .create_stream(contributor_identity, base_asset, 6_000, 1, 3, 2)
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    let reserved = instance.clone()
+        .methods()
+        .get_reserved_amount(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(reserved, 6_000);
+
+    // Reserved funds can't be withdrawn by the owner
+    let result = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 5_000, Identity::Address(owner.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InsufficientBalance(base_asset));
+
+    // Only the recipient can claim
+    let result = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(
+        result,
+        TreasuryError::Unauthorized(Identity::Address(owner.address().into())),
+    );
+
+    let balance_before = contributor.get_asset_balance(&base_asset).await.unwrap();
+    let claimed = instance.clone()
+        .with_account(contributor.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(claimed, 6_000);
+
+    let balance_after = contributor.get_asset_balance(&base_asset).await.unwrap();
+    assert!(balance_after > balance_before);
+
+    // Nothing left to claim
+    let result = instance
+        .with_account(contributor.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn test_stream_before_cliff_and_cancel() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let contributor = wallets.get(2).unwrap().clone();
+    let contributor_identity = Identity::Address(contributor.address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    // A schedule far in the future has nothing vested yet
+    let stream_id = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .create_stream(contributor_identity, base_asset, 8_000, u64::MAX - 2, u64::MAX, u64::MAX - 1)
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    let vested = instance.clone()
+        .methods()
+        .get_vested_amount(stream_id)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(vested, 0);
+
+    let result = instance.clone()
+        .with_account(contributor.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InvalidAmount);
+
+    // Streams can't be over-committed
+    let result = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .create_stream(contributor_identity, base_asset, 5_000, 1, 3, 2)
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InsufficientBalance(base_asset));
+
+    // Cancelling returns the unvested funds to the treasury
+    instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .cancel_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let stream = instance.clone()
+        .methods()
+        .get_stream(stream_id)
+        .call()
+        .await
+        .unwrap()
+        .value
+        .unwrap();
+    assert!(stream.cancelled);
+
+    let reserved = instance.clone()
+        .methods()
+        .get_reserved_amount(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(reserved, 0);
+
+    let result = instance
+        .with_account(owner.clone())
+        .methods()
+        .withdraw(base_asset, 8_000, Identity::Address(owner.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_stream_vests_linearly() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let contributor = wallets.get(2).unwrap().clone();
+    let contributor_identity = Identity::Address(contributor.address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+    let provider = owner.try_provider().unwrap();
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    // Vests 8_000 over 1_000 seconds starting now, with a 100 second cliff
+    let start = block_timestamp(provider).await;
+    let stream_id = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .create_stream(contributor_identity, base_asset, 8_000, start, start + 1_000, start + 100)
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    // Halfway through, about half has vested
+    advance_time(provider, 500).await;
+    let claimed = instance.clone()
+        .with_account(contributor.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert!((4_000..4_100).contains(&claimed), "claimed {claimed} halfway through");
+
+    let reserved = instance.clone()
+        .methods()
+        .get_reserved_amount(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(reserved, 8_000 - claimed);
+
+    // After the end the rest is claimable
+    advance_time(provider, 1_000).await;
+    let rest = instance.clone()
+        .with_account(contributor.clone())
+        .methods()
+        .claim_stream(stream_id)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(claimed + rest, 8_000);
+
+    let stream = instance
+        .methods()
+        .get_stream(stream_id)
+        .call()
+        .await
+        .unwrap()
+        .value
+        .unwrap();
+    assert_eq!(stream.claimed, 8_000);
+}
+
+#[tokio::test]
+async fn test_owed_fees_and_streams_are_kept_apart() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    // The deployer wallet is both the owner and the authorized strategy
+    let owner = wallets.get(0).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    let contributor_identity = Identity::Address(wallets.get(2).unwrap().address().into());
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    instance.clone()
+        .methods()
+        .receive_fees()
+        .call_params(CallParameters::new(10_000, base_asset, 1_000_000))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+
+    // Fees owed to the strategy can't back a stream or be withdrawn
+    let result = instance.clone()
+        .methods()
+        .create_stream(contributor_identity, base_asset, 10_000, u64::MAX - 2, u64::MAX, u64::MAX - 1)
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InsufficientBalance(base_asset));
+
+    let result = instance.clone()
+        .methods()
+        .withdraw(base_asset, 1, owner_identity)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InsufficientBalance(base_asset));
+
+    // The treasury's own funds can
+    fund_treasury(&owner, treasury_id, 10_000).await;
+    instance.clone()
+        .methods()
+        .create_stream(contributor_identity, base_asset, 10_000, u64::MAX - 2, u64::MAX, u64::MAX - 1)
+        .call()
+        .await
+        .unwrap();
+
+    let result = instance.clone()
+        .methods()
+        .withdraw(base_asset, 1, owner_identity)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::InsufficientBalance(base_asset));
+
+    // The strategy still gets its fees, leaving the stream backed
+    instance.clone()
+        .methods()
+        .withdraw_fees()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let treasury_balance = owner
+        .try_provider()
+        .unwrap()
+        .get_contract_asset_balance(&Bech32ContractId::from(treasury_id), base_asset)
+        .await
+        .unwrap();
+    assert_eq!(treasury_balance, 10_000);
+    let reserved = instance
+        .methods()
+        .get_reserved_amount(base_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(reserved, 10_000);
+}
+
+#[tokio::test]
+async fn test_only_owner_can_create_stream() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let non_owner = wallets.get(1).unwrap().clone();
+
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let result = instance
+        .with_account(non_owner.clone())
+        .methods()
+        .create_stream(Identity::Address(non_owner.address().into()), AssetId::from(BASE_ASSET_ID), 1_000, 1, 3, 2)
+        .call()
+        .await;
+
+    assert_reverted_with(
+        result,
+        TreasuryError::Unauthorized(Identity::Address(non_owner.address().into())),
+    );
+}
+
+// Deploys a strategy and the mock AMM, registers the strategy with the treasury
+// and seeds the AMM with `liquidity` receipt tokens to buy back.
+async fn setup_buyback(
+    instance: &TreasuryContract<WalletUnlocked>,
+    wallet: &WalletUnlocked,
+    liquidity: u64,
+) -> (StrategyContract<WalletUnlocked>, ContractId, ContractId) {
+    let configurables = StrategyContractConfigurables::default()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(wallet.address().into())))
+        .unwrap();
+    let strategy_id = Contract::load_from(
+        "contracts/strategy/out/debug/strategy.bin",
+        LoadConfiguration::default().with_configurables(configurables),
+    )
+    .unwrap()
+    .deploy(wallet, TxPolicies::default())
+    .await
+    .unwrap();
+    let strategy_instance = StrategyContract::new(strategy_id.clone(), wallet.clone());
+
+    let mira_id = Contract::load_from(
+        "contracts/mockMira/out/debug/mockMira.bin",
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .deploy(wallet, TxPolicies::default())
+    .await
+    .unwrap();
+
+    let strategy_id: ContractId = strategy_id.into();
+    let mira_id: ContractId = mira_id.into();
+
+    strategy_instance.clone()
+        .methods()
+        .constructor(
+            ContractId::from([1u8; 32]),
+            wallet.address(),
+            "Sprout Basket".to_string(),
+            "SPRT".to_string(),
+            String::new(),
+            String::new(),
+        )
+        .call()
+        .await
+        .unwrap();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_owner()
+        .call()
+        .await
+        .unwrap();
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(Bits256::zeroed(), vec![TokenAllocation {
+            token: base_asset,
+            p_id: (base_asset, base_asset, false),
+            percentage: 10000,
+        }])
+        .call()
+        .await
+        .unwrap();
+
+    // The mock AMM's pool holds real shares against an equal base reserve
+    strategy_instance.clone()
+        .methods()
+        .deposit(Identity::ContractId(mira_id), Bits256::zeroed())
+        .call_params(CallParameters::default()
+            .with_amount(liquidity)
+            .with_asset_id(base_asset))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+    wallet
+        .force_transfer_to_contract(&Bech32ContractId::from(mira_id), liquidity, base_asset, TxPolicies::default())
+        .await
+        .unwrap();
+
+    instance.clone()
+        .with_account(wallet.clone())
+        .methods()
+        .add_strategy(Identity::ContractId(strategy_id))
+        .call()
+        .await
+        .unwrap();
+
+    (strategy_instance, strategy_id, mira_id)
+}
+
+#[tokio::test]
+async fn test_buyback_and_burn() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    let (strategy_instance, strategy_id, mira_id) = setup_buyback(&instance, &owner, 5_000).await;
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let receipt_asset = strategy_instance.clone()
+        .methods()
+        .asset_id(Bits256::zeroed())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    let pool_id = (base_asset, receipt_asset, false);
+
+    // All of the 1_000 in is swapped: 1_000 * 0.997 * 5_000 / (5_000 + 997)
+    let response = instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .buyback_and_burn(strategy_id, Bits256::zeroed(), mira_id, pool_id, 1_000, 800)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+    let burned = response.value;
+    assert_eq!(burned, 831);
+
+    let events = response.decode_logs_with_type::<BuybackExecuted>().unwrap();
+    assert_eq!(
+        events,
+        vec![BuybackExecuted {
+            strategy: strategy_id,
+            vault_id: Bits256::zeroed(),
+            amount_in: 1_000,
+            amount_burned: 831,
+        }]
+    );
+
+    // The bought back receipt tokens were burned
+    let total_supply = strategy_instance
+        .methods()
+        .total_supply(receipt_asset)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(total_supply, Some(5_000 - 831));
+}
+
+#[tokio::test]
+async fn test_buyback_slippage_exceeded() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    // A 500/500 pool pays out 333 for 1_000, less than the minimum we ask for
+    let (strategy_instance, strategy_id, mira_id) = setup_buyback(&instance, &owner, 500).await;
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let receipt_asset = strategy_instance
+        .methods()
+        .asset_id(Bits256::zeroed())
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    let result = instance
+        .with_account(owner.clone())
+        .methods()
+        .buyback_and_burn(strategy_id, Bits256::zeroed(), mira_id, (base_asset, receipt_asset, false), 1_000, 1_000)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .call()
+        .await;
+    assert_reverted_with(result, TreasuryError::SlippageExceeded(333));
+}
+
+#[tokio::test]
+async fn test_buyback_accrues_to_holders() {
+    let (instance, treasury_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let holder = wallets.get(1).unwrap().clone();
+    let base_asset = AssetId::from(BASE_ASSET_ID);
+
+    let (strategy_instance, strategy_id, mira_id) = setup_buyback(&instance, &owner, 5_000).await;
+    fund_treasury(&owner, treasury_id, 10_000).await;
+
+    let as_holder = strategy_instance.clone().with_account(holder.clone());
+    as_holder
+        .methods()
+        .deposit(Identity::Address(holder.address().into()), Bits256::zeroed())
+        .call_params(CallParameters::default()
+            .with_amount(100_000)
+            .with_asset_id(base_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    let receipt_asset = as_holder.methods().asset_id(Bits256::zeroed()).call().await.unwrap().value;
+
+    // What the holder's 100_000 shares redeem for
+    let redeemable = |managed: u64, supply: u64| (managed as u128 * 100_000 / supply as u128) as u64;
+    let managed = as_holder.methods().managed_assets(base_asset, Bits256::zeroed()).call().await.unwrap().value;
+    let supply = as_holder.methods().total_supply(receipt_asset).call().await.unwrap().value.unwrap();
+    assert_eq!(redeemable(managed, supply), 100_000);
+
+    instance
+        .with_account(owner.clone())
+        .methods()
+        .buyback_and_burn(strategy_id, Bits256::zeroed(), mira_id, (base_asset, receipt_asset, false), 1_000, 800)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    // The vault kept its assets while the share count shrank
+    let managed_after = as_holder.methods().managed_assets(base_asset, Bits256::zeroed()).call().await.unwrap().value;
+    let supply_after = as_holder.methods().total_supply(receipt_asset).call().await.unwrap().value.unwrap();
+    assert_eq!(managed_after, managed);
+    assert_eq!(supply_after, supply - 831);
+    let value_after = redeemable(managed_after, supply_after);
+    assert!(value_after > 100_000);
+
+    let withdrawn = as_holder
+        .methods()
+        .withdraw(Identity::Address(holder.address().into()), base_asset, Bits256::zeroed())
+        .call_params(CallParameters::default()
+            .with_amount(100_000)
+            .with_asset_id(receipt_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(withdrawn, value_after);
+}
@@ -0,0 +1,457 @@
+use fuels::{
+    prelude::*,
+    types::{Bits256, Identity},
+};
+
+mod common;
+use common::revert::assert_reverted_with;
+
+abigen!(Contract(
+    name = "FungibleContract",
+    abi = "contracts/fungible/out/debug/fungible-abi.json"
+));
+
+async fn get_contract_instance() -> (FungibleContract<WalletUnlocked>, Bech32ContractId, WalletUnlocked, WalletUnlocked) {
+    let mut wallets = launch_custom_provider_and_get_wallets(
+        WalletsConfig::new(Some(2), Some(1), Some(1_000_000_000)),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let stranger = wallets.pop().unwrap();
+    let owner = wallets.pop().unwrap();
+
+    // The proxy owner is fixed at deployment
+    let configurables = FungibleContractConfigurables::default()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(owner.address().into())))
+        .unwrap();
+
+    let id = Contract::load_from(
+        "contracts/fungible/out/debug/fungible.bin",
+        LoadConfiguration::default().with_configurables(configurables),
+    )
+    .unwrap()
+    .deploy(&owner, TxPolicies::default())
+    .await
+    .unwrap();
+
+    let instance = FungibleContract::new(id.clone(), owner.clone());
+    instance
+        .methods()
+        .initialize_owner()
+        .call()
+        .await
+        .unwrap();
+
+    (instance, id, owner, stranger)
+}
+
+#[tokio::test]
+async fn test_stranger_cannot_set_metadata() {
+    let (instance, id, _owner, stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let stranger_identity = Identity::Address(stranger.address().into());
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .set_name(asset_id, "Hijacked".to_string())
+        .call()
+        .await;
+    assert_reverted_with(result, Error::Unauthorized(stranger_identity));
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .set_symbol(asset_id, "HJK".to_string())
+        .call()
+        .await;
+    assert_reverted_with(result, Error::Unauthorized(stranger_identity));
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .set_decimals(asset_id, 0)
+        .call()
+        .await;
+    assert_reverted_with(result, Error::Unauthorized(stranger_identity));
+
+    // Nothing was written
+    let name = instance.methods().name(asset_id).call().await.unwrap().value;
+    assert_eq!(name, None);
+}
+
+#[tokio::test]
+async fn test_owner_sets_metadata_with_events() {
+    let (instance, id, owner, _stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let owner_identity = Identity::Address(owner.address().into());
+
+    let response = instance
+        .methods()
+        .set_name(asset_id, "Sprout Receipt".to_string())
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SetNameEvent>().unwrap();
+    assert_eq!(
+        events,
+        vec![SetNameEvent {
+            asset: asset_id,
+            name: Some("Sprout Receipt".to_string()),
+            sender: owner_identity,
+        }]
+    );
+
+    let response = instance
+        .methods()
+        .set_symbol(asset_id, "SPRT".to_string())
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SetSymbolEvent>().unwrap();
+    assert_eq!(events[0].symbol, Some("SPRT".to_string()));
+
+    let response = instance
+        .methods()
+        .set_decimals(asset_id, 9)
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SetDecimalsEvent>().unwrap();
+    assert_eq!(events[0].decimals, 9);
+
+    let decimals = instance.methods().decimals(asset_id).call().await.unwrap().value;
+    assert_eq!(decimals, Some(9));
+}
+
+#[tokio::test]
+async fn test_burn_forwarded_coins() {
+    let (instance, id, owner, _stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let owner_identity = Identity::Address(owner.address().into());
+
+    instance
+        .methods()
+        .mint(owner_identity, Bits256::zeroed(), 100)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(owner.get_asset_balance(&asset_id).await.unwrap(), 100);
+
+    instance
+        .methods()
+        .burn(Bits256::zeroed(), 40)
+        .call_params(
+            CallParameters::default()
+                .with_amount(40)
+                .with_asset_id(asset_id),
+        )
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(owner.get_asset_balance(&asset_id).await.unwrap(), 60);
+    let supply = instance.methods().total_supply(asset_id).call().await.unwrap().value;
+    assert_eq!(supply, Some(60));
+}
+
+#[tokio::test]
+async fn test_burn_rejects_mismatched_coins() {
+    let (instance, id, owner, _stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let owner_identity = Identity::Address(owner.address().into());
+
+    instance
+        .methods()
+        .mint(owner_identity, Bits256::zeroed(), 100)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    // Forwarding fewer coins than the amount to burn
+    let result = instance
+        .methods()
+        .burn(Bits256::zeroed(), 50)
+        .call_params(
+            CallParameters::default()
+                .with_amount(10)
+                .with_asset_id(asset_id),
+        )
+        .unwrap()
+        .call()
+        .await;
+    assert_reverted_with(result, Error::IncorrectAmountProvided(10));
+
+    // Forwarding the base asset instead of the token
+    let result = instance
+        .methods()
+        .burn(Bits256::zeroed(), 50)
+        .call_params(
+            CallParameters::default()
+                .with_amount(50)
+                .with_asset_id(AssetId::zeroed()),
+        )
+        .unwrap()
+        .call()
+        .await;
+    assert_reverted_with(result, Error::IncorrectAssetProvided(AssetId::zeroed()));
+
+    let supply = instance.methods().total_supply(asset_id).call().await.unwrap().value;
+    assert_eq!(supply, Some(100));
+}
+
+#[tokio::test]
+async fn test_only_configured_proxy_owner_initializes() {
+    let (instance, _id, _owner, stranger) = get_contract_instance().await;
+    let target = ContractId::new([0x44; 32]);
+    let stranger_identity = Identity::Address(stranger.address().into());
+
+    // Only the proxy owner configured at deployment can claim the proxy
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .initialize(target)
+        .call()
+        .await;
+    assert_reverted_with(result, Error::NotProxyOwner(stranger_identity));
+
+    instance.methods().initialize(target).call().await.unwrap();
+    let implementation = instance.methods().get_implementation().call().await.unwrap().value;
+    assert_eq!(implementation, Some(target));
+
+    let result = instance.methods().initialize(ContractId::new([0x55; 32])).call().await;
+    assert_reverted_with(result, Error::AlreadyInitialized);
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .set_implementation(ContractId::new([0x66; 32]))
+        .call()
+        .await;
+    assert_reverted_with(result, Error::NotProxyOwner(stranger_identity));
+
+    let new_target = ContractId::new([0x77; 32]);
+    instance
+        .methods()
+        .set_implementation(new_target)
+        .call()
+        .await
+        .unwrap();
+    let implementation = instance.methods().get_implementation().call().await.unwrap().value;
+    assert_eq!(implementation, Some(new_target));
+}
+
+#[tokio::test]
+async fn test_sub_id_reserved_for_one_strategy() {
+    let (instance, _id, _owner, stranger) = get_contract_instance().await;
+    let strategy = ContractId::new([0x16; 32]);
+    let sibling = ContractId::new([0x17; 32]);
+    let sub_id = Bits256([1u8; 32]);
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .assign_sub_id(strategy, sub_id)
+        .call()
+        .await;
+    assert_reverted_with(
+        result,
+        Error::Unauthorized(Identity::Address(stranger.address().into())),
+    );
+
+    let response = instance
+        .methods()
+        .assign_sub_id(strategy, sub_id)
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SubIdAssigned>().unwrap();
+    assert_eq!(events, vec![SubIdAssigned { strategy, sub_id }]);
+
+    // A sibling strategy can't take over the sub-identifier
+    let result = instance
+        .methods()
+        .assign_sub_id(sibling, sub_id)
+        .call()
+        .await;
+    assert_reverted_with(result, Error::SubIdAlreadyAssigned(strategy));
+
+    let assigned = instance.methods().sub_id_strategy(sub_id).call().await.unwrap().value;
+    assert_eq!(assigned, Some(strategy));
+}
+
+#[tokio::test]
+async fn test_strategy_supply_cap() {
+    let (instance, _id, _owner, stranger) = get_contract_instance().await;
+    let strategy = ContractId::new([0x16; 32]);
+
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .set_strategy_supply_cap(strategy, Some(1_000))
+        .call()
+        .await;
+    assert_reverted_with(
+        result,
+        Error::Unauthorized(Identity::Address(stranger.address().into())),
+    );
+
+    let response = instance
+        .methods()
+        .set_strategy_supply_cap(strategy, Some(1_000))
+        .call()
+        .await
+        .unwrap();
+    let events = response
+        .decode_logs_with_type::<StrategySupplyCapUpdated>()
+        .unwrap();
+    assert_eq!(
+        events,
+        vec![StrategySupplyCapUpdated {
+            strategy,
+            cap: Some(1_000),
+        }]
+    );
+
+    let cap = instance.methods().strategy_supply_cap(strategy).call().await.unwrap().value;
+    assert_eq!(cap, Some(1_000));
+    let supply = instance.methods().strategy_supply(strategy).call().await.unwrap().value;
+    assert_eq!(supply, 0);
+
+    instance
+        .methods()
+        .set_strategy_supply_cap(strategy, None)
+        .call()
+        .await
+        .unwrap();
+    let cap = instance.methods().strategy_supply_cap(strategy).call().await.unwrap().value;
+    assert_eq!(cap, None);
+}
+
+#[tokio::test]
+async fn test_transfer_contract_held_coins() {
+    let (instance, id, owner, stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let stranger_identity = Identity::Address(stranger.address().into());
+
+    instance
+        .methods()
+        .mint(Identity::ContractId(id.clone().into()), Bits256::zeroed(), 100)
+        .call()
+        .await
+        .unwrap();
+
+    // Strangers can't move coins held by the contract
+    let result = instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .transfer(stranger_identity, Bits256::zeroed(), 40)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, Error::Unauthorized(stranger_identity));
+
+    let result = instance
+        .methods()
+        .transfer(stranger_identity, Bits256::zeroed(), 101)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, Error::InsufficientBalance(100));
+
+    let response = instance
+        .methods()
+        .transfer(stranger_identity, Bits256::zeroed(), 40)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<Transferred>().unwrap();
+    assert_eq!(
+        events,
+        vec![Transferred {
+            sub_id: Bits256::zeroed(),
+            amount: 40,
+            to: stranger_identity,
+            sender: Identity::Address(owner.address().into()),
+        }]
+    );
+
+    assert_eq!(stranger.get_asset_balance(&asset_id).await.unwrap(), 40);
+    let held = instance.methods().this_balance(Bits256::zeroed()).call().await.unwrap().value;
+    assert_eq!(held, 60);
+}
+
+#[tokio::test]
+async fn test_supply_events_and_max_supply() {
+    let (instance, id, owner, _stranger) = get_contract_instance().await;
+    let asset_id = id.asset_id(&Bits256::zeroed());
+    let owner_identity = Identity::Address(owner.address().into());
+
+    let max_supply = instance.methods().max_supply(asset_id).call().await.unwrap().value;
+    assert_eq!(max_supply, None);
+
+    let response = instance
+        .methods()
+        .mint(owner_identity, Bits256::zeroed(), 100)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<TotalSupplyEvent>().unwrap();
+    assert_eq!(
+        events,
+        vec![TotalSupplyEvent {
+            asset: asset_id,
+            supply: 100,
+            sender: owner_identity,
+        }]
+    );
+
+    let max_supply = instance
+        .methods()
+        .max_supply(asset_id)
+        .call()
+        .await
+        .unwrap()
+        .value
+        .unwrap();
+    assert_eq!(max_supply, 1_000_000_000_000_000_000);
+
+    // Minting past the max supply reverts
+    let result = instance
+        .methods()
+        .mint(owner_identity, Bits256::zeroed(), max_supply)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, Error::MaxSupplyReached(max_supply));
+
+    let response = instance
+        .methods()
+        .burn(Bits256::zeroed(), 30)
+        .call_params(
+            CallParameters::default()
+                .with_amount(30)
+                .with_asset_id(asset_id),
+        )
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<TotalSupplyEvent>().unwrap();
+    assert_eq!(events[0].supply, 70);
+}
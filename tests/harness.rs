@@ -1,7 +1,9 @@
 use fuels::{
     prelude::*, 
     client::FuelClient,
+    crypto::{Hasher, Message},
     types::{
+        B512,
         Bytes32,
         Identity, 
         SizedAsciiString,
@@ -12,6 +14,14 @@ use fuels::{
 };
 use fuel_core_client::client::types::TransactionStatus;
 
+mod common;
+use common::revert::assert_reverted_with;
+use common::time::{advance_time, block_timestamp};
+use abigen_bindings::strategy_contract_mod::Error as StrategyError;
+use abigen_bindings::sprout_token_mod::Error as SproutTokenError;
+use abigen_bindings::mock_sprout_token_mod::Error as FungibleError;
+use abigen_bindings::mira_amm_mod::MockError as MiraError;
+
 
 abigen!(
     Contract(
@@ -22,16 +32,37 @@ abigen!(
         name = "MiraAMM",
         abi = "contracts/mockMira/out/debug/mockMira-abi.json"
     ),
+    // The mock token is the real fungible contract, deployed fresh for each test
     Contract(
         name = "MockSproutToken",
-       abi = "contracts/mocktoken/out/debug/mocktoken-abi.json"
+        abi = "contracts/fungible/out/debug/fungible-abi.json"
+    ),
+    Contract(
+        name = "SproutToken",
+        abi = "contracts/sprout-token/out/debug/sprout-token-abi.json"
+    ),
+    Script(
+        name = "LimitOrderScript",
+        abi = "contracts/limit-order-script/out/debug/limit-order-script-abi.json"
+    ),
+    Predicate(
+        name = "LimitOrderPredicate",
+        abi = "contracts/limit-order/out/debug/limit-order-abi.json"
     ));
 
 
 const BASE_ASSET_ID: [u8; 32] = [0u8; 32];
-const SCALE: u64 = 10000; // Same as in your contract 
+const SCALE: u64 = 10000; // Same as in your contract
+// The strategy's default `MAX_SUPPLY` of shares per vault
+const MAX_SUPPLY: u64 = 1_000_000_000_000_000_000;
+// The vault every strategy is constructed with
+const DEFAULT_VAULT: Bits256 = Bits256([0u8; 32]);
+const DEFAULT_VAULT_NAME: &str = "Sprout FUEL/USDC Basket";
+const DEFAULT_VAULT_SYMBOL: &str = "sFUSD";
+const DEFAULT_VAULT_DESCRIPTION: &str = "Even split between FUEL and USDC";
+const DEFAULT_VAULT_ICON_URI: &str = "https://sprout.finance/icons/sfusd.svg"; 
 
-async fn get_contract_instance() -> (
+type Deployment = (
     StrategyContract<WalletUnlocked>, 
     MiraAMM<WalletUnlocked>, 
     MockSproutToken<WalletUnlocked>, // Add the mock token instance
@@ -39,7 +70,18 @@ async fn get_contract_instance() -> (
     ContractId, 
     ContractId, // Add token contract ID
     Vec<WalletUnlocked>
-) {
+);
+
+async fn get_contract_instance() -> Deployment {
+    deploy_contracts(false).await
+}
+
+// Deploys a strategy whose receipts are minted and burned by the token contract
+async fn get_external_receipt_instance() -> Deployment {
+    deploy_contracts(true).await
+}
+
+async fn deploy_contracts(external_receipt_token: bool) -> Deployment {
     let wallets = launch_custom_provider_and_get_wallets(
         WalletsConfig::new(Some(3), Some(1), Some(1_000_000_000)),
         None,
@@ -52,7 +94,7 @@ async fn get_contract_instance() -> (
     println!("Deployer wallet address: {:?}", wallet.address());
 
     let token_id = Contract::load_from(
-        "contracts/mocktoken/out/debug/mocktoken.bin", 
+        "contracts/fungible/out/debug/fungible.bin",
         LoadConfiguration::default(),
     )
     .unwrap()
@@ -76,9 +118,14 @@ async fn get_contract_instance() -> (
     
      // Initialize token contract with reference to strategy
 
+     let strategy_configurables = StrategyContractConfigurables::default()
+        .with_EXTERNAL_RECEIPT_TOKEN(external_receipt_token)
+        .unwrap()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(wallet.address().into())))
+        .unwrap();
      let strategy_id = Contract::load_from(
         "contracts/strategy/out/debug/strategy.bin",
-        LoadConfiguration::default(),
+        LoadConfiguration::default().with_configurables(strategy_configurables),
     )
     .unwrap()
     .deploy(&wallet, TxPolicies::default())
@@ -115,11 +162,27 @@ let is_approved = token_instance.clone()
 println!("Strategy approval status: {}", is_approved);
 assert!(is_approved, "Strategy was not approved in token contract");
 
+// The strategy may only mint the default vault's receipts on the token
+token_instance.clone()
+    .with_account(wallet.clone())
+    .methods()
+    .assign_sub_id(strategy_id.clone(), DEFAULT_VAULT)
+    .call()
+    .await
+    .unwrap();
+
 // Initialize strategy with token ID
 strategy_instance.clone()
     .with_account(wallet.clone())
     .methods()
-    .constructor(&token_id, wallet.address())
+    .constructor(
+        &token_id,
+        wallet.address(),
+        DEFAULT_VAULT_NAME.to_string(),
+        DEFAULT_VAULT_SYMBOL.to_string(),
+        DEFAULT_VAULT_DESCRIPTION.to_string(),
+        DEFAULT_VAULT_ICON_URI.to_string(),
+    )
     .call()
     .await
     .unwrap();
@@ -189,7 +252,7 @@ async fn test_owner_initialization() {
         .call()
         .await;
     
-    assert!(result.is_err());
+    assert_reverted_with(result, StrategyError::OwnerAlreadyInitialized);
 }
 
 #[tokio::test]
@@ -242,7 +305,7 @@ async fn test_withdrawal_fee() {
     // Test initial fee is 0
     let initial_fee = strategy_instance
         .methods()
-        .get_withdrawal_fee()
+        .get_withdrawal_fee(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -254,7 +317,7 @@ async fn test_withdrawal_fee() {
     strategy_instance.clone()
         .with_account(owner.clone())
         .methods()
-        .set_withdrawal_fee(new_fee)
+        .set_withdrawal_fee(DEFAULT_VAULT, new_fee)
         .call()
         .await
         .unwrap();
@@ -262,7 +325,7 @@ async fn test_withdrawal_fee() {
     // Verify fee was set
     let updated_fee = strategy_instance
         .methods()
-        .get_withdrawal_fee()
+        .get_withdrawal_fee(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -272,12 +335,15 @@ async fn test_withdrawal_fee() {
     // Test non-owner cannot set fee
     let non_owner = wallets.get(1).unwrap().clone();
     let result = strategy_instance
-        .with_account(non_owner)
+        .with_account(non_owner.clone())
         .methods()
-        .set_withdrawal_fee(200)
+        .set_withdrawal_fee(DEFAULT_VAULT, 200)
         .call()
         .await;
-    assert!(result.is_err());
+    assert_reverted_with(
+        result,
+        StrategyError::Unauthorized(Identity::Address(non_owner.address().into())),
+    );
 }
 
 #[tokio::test]
@@ -395,7 +461,7 @@ async fn test_target_tokens() {
     // Initially no tokens
     let initial_tokens = strategy_instance.clone()
         .methods()
-        .get_target_tokens()
+        .get_target_tokens(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -406,7 +472,7 @@ async fn test_target_tokens() {
     let test_token = AssetId::new([3u8; 32]);
     let initial_allocation = strategy_instance.clone()
         .methods()
-        .get_token_allocation(test_token)
+        .get_token_allocation(DEFAULT_VAULT, test_token)
         .call()
         .await
         .unwrap()
@@ -415,7 +481,6 @@ async fn test_target_tokens() {
 }
 
 #[tokio::test]
-#[should_panic]
 async fn only_owner_can_set_owner() {
     let (strategy_instance, mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
     // get access to test wallets
@@ -423,13 +488,13 @@ async fn only_owner_can_set_owner() {
     
     // this should fail - non-owner trying to set itself as owner
     let new_owner = Identity::Address(non_owner.address().into());
-    strategy_instance
+    let result = strategy_instance
         .with_account(non_owner.clone())
         .methods()
         .set_owner(new_owner)
         .call()
-        .await
-        .unwrap();
+        .await;
+    assert_reverted_with(result, StrategyError::Unauthorized(new_owner));
 }
 
 //Integrating the tests that use Mira
@@ -438,12 +503,13 @@ async fn only_owner_can_set_owner() {
 async fn test_mira_basic_swap() {
     let (strategy_instance, mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
     let wallet = wallets.get(0).unwrap().clone();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
 
     // Create a pool ID for testing
     let pool_id = (
-        AssetId::from([1u8; 32]),  // token0
-        AssetId::from([2u8; 32]),  // token1
-        false                         // stable
+        AssetId::zeroed(),  // token0
+        usdc_asset,         // token1
+        false               // stable
     );
 
     // Create empty bytes for data parameter
@@ -458,11 +524,27 @@ async fn test_mira_basic_swap() {
             1000,  // amount_0_out
             1000,  // amount_1_out
             Identity::Address(wallet.address().into()),
-            empty_bytes
+            empty_bytes.clone()
         )
         .call()
         .await
         .unwrap();
+
+    // A pool without liquidity can't pay out
+    let unfunded = AssetId::from([1u8; 32]);
+    let result = mira_instance
+        .with_account(wallet.clone())
+        .methods()
+        .swap(
+            (unfunded, usdc_asset, false),
+            1000,
+            0,
+            Identity::Address(wallet.address().into()),
+            empty_bytes
+        )
+        .call()
+        .await;
+    assert_reverted_with(result, MiraError::InsufficientLiquidity(unfunded));
 }
 
 #[tokio::test]
@@ -473,16 +555,18 @@ async fn test_strategy_mira_integration() {
     // Setup the tokens for testing
     let token0 = AssetId::new([1u8; 32]);  // Changed to AssetId
     let token1 = AssetId::from([2u8; 32]);
+    // Swaps go through a pool the mock can pay out of
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &owner, mira_id, 1_000_000).await;
     let pool_id = (
-        AssetId::from([1u8; 32]),
-        AssetId::from([2u8; 32]),
+        AssetId::zeroed(),
+        usdc_asset,
         false
     );
 
     // Verify token allocation (if this method exists in your contract)
     let token_allocation = strategy_instance
         .methods()
-        .get_token_allocation(token0)
+        .get_token_allocation(DEFAULT_VAULT, token0)
         .call()
         .await
         .unwrap()
@@ -521,7 +605,7 @@ async fn test_strategy_mira_integration() {
             
             // Define USDC token ID (you'd use the real USDC asset ID in production)
             let fuel_asset = AssetId::zeroed();
-            let usdc_asset = AssetId::new([0x75, 0x73, 0x64, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]); // Example USDC asset ID
+            let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
             
             // Create pool ID for FUEL/USDC
             let pool_id: (AssetId, AssetId, bool) = (
@@ -548,7 +632,7 @@ async fn test_strategy_mira_integration() {
             strategy_instance.clone()
                 .with_account(wallet.clone())
                 .methods()
-                .initialize_token_allocations(token_allocations.clone())
+                .initialize_token_allocations(DEFAULT_VAULT, token_allocations.clone())
                 .call()
                 .await
                 .unwrap();
@@ -566,7 +650,7 @@ async fn test_strategy_mira_integration() {
             // This is the asset that will be minted directly to the user now
             let receipt_asset_id = strategy_instance.clone()
                 .methods()
-                .asset_id()
+                .asset_id(DEFAULT_VAULT)
                 .call()
                 .await
                 .unwrap()
@@ -593,7 +677,7 @@ async fn test_strategy_mira_integration() {
             let result = strategy_instance.clone()
                 .with_account(wallet.clone())
                 .methods()
-                .deposit()
+                .deposit(Identity::Address(wallet.address().into()), DEFAULT_VAULT)
                 .call_params(deposit_params)
                 .unwrap()
                 .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
@@ -645,7 +729,7 @@ async fn test_successful_withdraw() {
     
     // Set up token allocations as before
     let fuel_asset = AssetId::zeroed();
-    let usdc_asset = AssetId::new([0x75, 0x73, 0x64, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
     
     println!("Setting up token allocations...");
     // Create pool ID for FUEL/USDC
@@ -674,7 +758,7 @@ async fn test_successful_withdraw() {
     strategy_instance.clone()
         .with_account(wallet.clone())
         .methods()
-        .initialize_token_allocations(token_allocations.clone())
+        .initialize_token_allocations(DEFAULT_VAULT, token_allocations.clone())
         .call()
         .await
         .unwrap();
@@ -693,7 +777,7 @@ async fn test_successful_withdraw() {
     println!("Getting receipt token asset ID...");
     let receipt_asset_id = strategy_instance.clone()
         .methods()
-        .asset_id()
+        .asset_id(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -714,7 +798,7 @@ async fn test_successful_withdraw() {
     let deposit_result = strategy_instance.clone()
         .with_account(wallet.clone())
         .methods()
-        .deposit()
+        .deposit(Identity::Address(wallet.address().into()), DEFAULT_VAULT)
         .call_params(CallParameters::default()
             .with_amount(deposit_amount)
             .with_asset_id(fuel_asset))
@@ -777,7 +861,7 @@ async fn test_successful_withdraw() {
     // Get the current token allocations
     let token_allocations = strategy_instance.clone()
         .methods()
-        .get_target_tokens()
+        .get_target_tokens(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -787,7 +871,7 @@ async fn test_successful_withdraw() {
     // Get the current withdrawal fee
     let withdrawal_fee = strategy_instance.clone()
         .methods()
-        .get_withdrawal_fee()
+        .get_withdrawal_fee(DEFAULT_VAULT)
         .call()
         .await
         .unwrap()
@@ -825,7 +909,7 @@ async fn test_successful_withdraw() {
     let withdraw_result = strategy_instance.clone()
         .with_account(wallet.clone())
         .methods()
-        .withdraw()
+        .withdraw(Identity::Address(wallet.address().into()), AssetId::zeroed(), DEFAULT_VAULT)
         .call_params(CallParameters::default()
             .with_amount(withdraw_amount)
             .with_asset_id(receipt_asset_id))
@@ -860,4 +944,2162 @@ async fn test_successful_withdraw() {
     println!("Final FUEL balance: {}", final_fuel_balance);
     
     println!("=== test_successful_withdraw completed successfully ===\n");
-}
\ No newline at end of file
+}
+/// Sends `amount` of `asset` straight to the strategy, outside of any deposit.
+async fn send_stray_asset(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    strategy_id: ContractId,
+    wallet: &WalletUnlocked,
+    asset: AssetId,
+    amount: u64,
+) {
+    let strategy_address: Bech32Address = Address::new(*strategy_id).into();
+    strategy_instance.clone()
+        .with_account(wallet.clone())
+        .methods()
+        .get_owner()
+        .add_custom_asset(asset, amount, Some(strategy_address))
+        .call()
+        .await
+        .unwrap();
+}
+
+/// Allocates the whole basket to a single non-base token.
+async fn init_usdc_only_allocation(strategy_instance: &StrategyContract<WalletUnlocked>) -> AssetId {
+    let usdc_asset = AssetId::new([0x75, 0x73, 0x64, 0x63, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    let pool_id: (AssetId, AssetId, bool) = (AssetId::zeroed(), usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![TokenAllocation {
+            token: usdc_asset,
+            p_id: pool_id,
+            percentage: 10000,
+        }])
+        .call()
+        .await
+        .unwrap();
+
+    usdc_asset
+}
+
+#[tokio::test]
+async fn test_sweep_stray_asset() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, _mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = wallets.get(1).unwrap().clone();
+    init_usdc_only_allocation(&strategy_instance).await;
+
+    // The base asset is not in the basket, so anything sent here is stray
+    let stray_asset = AssetId::zeroed();
+    send_stray_asset(&strategy_instance, strategy_id, &owner, stray_asset, 50_000).await;
+
+    let recipient_before = recipient.get_asset_balance(&stray_asset).await.unwrap();
+
+    let response = strategy_instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .sweep(stray_asset, 30_000, Identity::Address(recipient.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    let recipient_after = recipient.get_asset_balance(&stray_asset).await.unwrap();
+    assert_eq!(recipient_after - recipient_before, 30_000);
+
+    let events = response.decode_logs_with_type::<Swept>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].asset, stray_asset);
+    assert_eq!(events[0].amount, 30_000);
+    assert_eq!(events[0].recipient, Identity::Address(recipient.address().into()));
+
+    // Cannot sweep more than the strategy holds
+    let result = strategy_instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .sweep(stray_asset, 30_000, Identity::Address(recipient.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InsufficientBalance(20_000));
+}
+
+#[tokio::test]
+async fn test_sweep_refuses_protected_assets() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, _mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = Identity::Address(owner.address().into());
+    let usdc_asset = init_usdc_only_allocation(&strategy_instance).await;
+
+    // Basket tokens back depositors
+    let result = strategy_instance.clone()
+        .methods()
+        .sweep(usdc_asset, 1, recipient)
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ProtectedAsset(usdc_asset));
+
+    // So does the receipt asset
+    let receipt_asset_id = strategy_instance.clone()
+        .methods()
+        .asset_id(DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    let result = strategy_instance.clone()
+        .methods()
+        .sweep(receipt_asset_id, 1, recipient)
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ProtectedAsset(receipt_asset_id));
+}
+
+#[tokio::test]
+async fn test_sweep_refuses_deallocated_assets_still_held() {
+    let (strategy_instance, _mira_instance, token_instance, _strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let recipient = Identity::Address(owner.address().into());
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &owner, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+    let second_vault = Bits256([1u8; 32]);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+
+    // A second vault holds USDC of its own
+    strategy_instance.clone()
+        .methods()
+        .create_vault(second_vault, "Second".to_string(), "SND".to_string(), String::new(), String::new())
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(second_vault, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .deposit(recipient, second_vault)
+        .call_params(CallParameters::default()
+            .with_amount(50_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    // The default vault drops USDC and keeps only the base asset
+    strategy_instance.clone()
+        .methods()
+        .rebalance(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 10000 },
+        ])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    // USDC is no longer in the default basket but the second vault still holds it
+    let result = strategy_instance.clone()
+        .methods()
+        .sweep(usdc_asset, 1, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ProtectedAsset(usdc_asset));
+
+    // Nor is the base asset the default vault holds up for grabs
+    let result = strategy_instance.clone()
+        .methods()
+        .sweep(fuel_asset, 1, recipient)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ProtectedAsset(fuel_asset));
+}
+
+#[tokio::test]
+async fn test_only_owner_can_sweep() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, _mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(0).unwrap().clone();
+    let non_owner = wallets.get(1).unwrap().clone();
+    init_usdc_only_allocation(&strategy_instance).await;
+    send_stray_asset(&strategy_instance, strategy_id, &owner, AssetId::zeroed(), 10_000).await;
+
+    let result = strategy_instance.clone()
+        .with_account(non_owner.clone())
+        .methods()
+        .sweep(AssetId::zeroed(), 10_000, Identity::Address(non_owner.address().into()))
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(
+        result,
+        StrategyError::Unauthorized(Identity::Address(non_owner.address().into())),
+    );
+}
+
+async fn deposit_base(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    mira_id: ContractId,
+    amount: u64,
+) {
+    strategy_instance.clone()
+        .methods()
+        .deposit(Identity::Address(strategy_instance.account().address().into()), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(amount)
+            .with_asset_id(AssetId::zeroed()))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+}
+
+async fn contract_balance(wallet: &WalletUnlocked, contract_id: ContractId, asset: AssetId) -> u64 {
+    wallet
+        .try_provider()
+        .unwrap()
+        .get_contract_asset_balance(&contract_id.into(), asset)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_base_allocation_is_held_without_swap() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+
+    // Only the USDC half went through the pool
+    assert_eq!(contract_balance(&wallet, mira_id, fuel_asset).await, 1_050_000);
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 50_000);
+
+    let holdings = strategy_instance.clone()
+        .methods()
+        .get_basket_holdings(DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(holdings, vec![(usdc_asset, 47_500), (fuel_asset, 50_000)]);
+}
+
+#[tokio::test]
+async fn test_base_only_basket_withdraw_pays_out_directly() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, fuel_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+    assert_eq!(contract_balance(&wallet, mira_id, fuel_asset).await, 0);
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 100_000);
+
+    let receipt_asset_id = strategy_instance.clone()
+        .methods()
+        .asset_id(DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    // Withdrawing never touches the pool
+    strategy_instance.clone()
+        .methods()
+        .withdraw(Identity::Address(wallet.address().into()), AssetId::zeroed(), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(40_000)
+            .with_asset_id(receipt_asset_id))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 60_000);
+    assert_eq!(contract_balance(&wallet, mira_id, fuel_asset).await, 0);
+}
+
+/// Mints a USDC stand-in from the fungible token and seeds the mock pool with it
+/// and the base asset, so swaps in both directions pay out.
+async fn seed_usdc_pool(
+    token_instance: &MockSproutToken<WalletUnlocked>,
+    token_id: ContractId,
+    wallet: &WalletUnlocked,
+    mira_id: ContractId,
+    liquidity: u64,
+) -> AssetId {
+    let usdc_sub_id = Bits256([0x75; 32]);
+    token_instance.clone()
+        .methods()
+        .mint(Identity::ContractId(mira_id), usdc_sub_id, liquidity)
+        .call()
+        .await
+        .unwrap();
+    wallet
+        .force_transfer_to_contract(&mira_id.into(), liquidity, AssetId::zeroed(), TxPolicies::default())
+        .await
+        .unwrap();
+
+    Bech32ContractId::from(token_id).asset_id(&usdc_sub_id)
+}
+
+async fn withdraw_shares(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    mira_id: ContractId,
+    receiver: Identity,
+    shares: u64,
+) -> u64 {
+    let receipt_asset_id = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    strategy_instance.clone()
+        .methods()
+        .withdraw(receiver, AssetId::zeroed(), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(shares)
+            .with_asset_id(receipt_asset_id))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value
+}
+
+#[tokio::test]
+async fn test_mixed_basket_withdraws_pro_rata() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let receiver = wallets.get(1).unwrap().clone();
+    let receiver_identity = Identity::Address(receiver.address().into());
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    // The USDC half loses the 5% slippage on the way in
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(usdc_asset, 47_500), (fuel_asset, 50_000)]);
+
+    // 40% of the shares take 40% of each holding; the USDC part pays out
+    // what the pool actually returned for it: 19_000 at the 1_050_000 /
+    // 952_500 spot price, less 5%
+    let balance_before = receiver.get_asset_balance(&fuel_asset).await.unwrap();
+    let paid = withdraw_shares(&strategy_instance, mira_id, receiver_identity, 40_000).await;
+    assert_eq!(paid, 20_000 + 19_897);
+    assert_eq!(receiver.get_asset_balance(&fuel_asset).await.unwrap() - balance_before, paid);
+
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(usdc_asset, 28_500), (fuel_asset, 30_000)]);
+
+    // The last shares empty the vault without underflowing
+    let paid = withdraw_shares(&strategy_instance, mira_id, receiver_identity, 60_000).await;
+    assert_eq!(paid, 30_000 + 28_709);
+
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(usdc_asset, 0), (fuel_asset, 0)]);
+    assert_eq!(contract_balance(&wallet, strategy_id, usdc_asset).await, 0);
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 0);
+}
+
+#[tokio::test]
+async fn test_mixed_basket_base_part_is_paid_from_holdings() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let receiver = wallets.get(1).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 7500 },
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 2500 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    deposit_base(&strategy_instance, mira_id, 80_000).await;
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(fuel_asset, 60_000), (usdc_asset, 19_000)]);
+
+    // Half the shares: the held base asset is paid out directly, and only the
+    // USDC goes back through the pool
+    let pool_base_before = contract_balance(&wallet, mira_id, fuel_asset).await;
+    let paid = withdraw_shares(&strategy_instance, mira_id, Identity::Address(receiver.address().into()), 40_000).await;
+    assert_eq!(paid, 30_000 + 9_384);
+    assert_eq!(pool_base_before - contract_balance(&wallet, mira_id, fuel_asset).await, 9_384);
+
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(fuel_asset, 30_000), (usdc_asset, 9_500)]);
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 30_000);
+    assert_eq!(contract_balance(&wallet, strategy_id, usdc_asset).await, 9_500);
+}
+
+#[tokio::test]
+async fn test_unseeded_pool_rejects_swaps() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, _wallets) = get_contract_instance().await;
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = AssetId::new([0x75; 32]);
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    // The empty mock pool can't quote the USDC half, so nothing is swapped
+    // into it for free
+    let result = strategy_instance.clone()
+        .methods()
+        .deposit(Identity::Address(strategy_instance.account().address().into()), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(100_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::NoPoolLiquidity(pool_id));
+}
+
+#[tokio::test]
+async fn test_rebalance_carries_over_held_base_asset() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+
+    // Move to a 75/25 base/USDC basket: only the USDC quarter is swapped
+    strategy_instance.clone()
+        .methods()
+        .rebalance(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 7500 },
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 2500 },
+        ])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 75_000);
+    assert_eq!(contract_balance(&wallet, mira_id, fuel_asset).await, 1_025_000);
+}
+
+#[tokio::test]
+async fn test_vaults_are_independent() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+    let second_vault = Bits256([1u8; 32]);
+
+    let response = strategy_instance.clone()
+        .methods()
+        .create_vault(
+            second_vault,
+            "Sprout Stable".to_string(),
+            "SSTB".to_string(),
+            "Stablecoin basket".to_string(),
+            "https://sprout.finance/icons/sstb.svg".to_string(),
+        )
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<VaultCreated>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vault_id, second_vault);
+
+    let vaults = strategy_instance.clone().methods().get_vaults().call().await.unwrap().value;
+    assert_eq!(vaults, vec![DEFAULT_VAULT, second_vault]);
+    let total_assets = strategy_instance.clone().methods().total_assets().call().await.unwrap().value;
+    assert_eq!(total_assets, 2);
+
+    // Each vault has its own basket and fee
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(second_vault, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .set_withdrawal_fee(second_vault, 300)
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        strategy_instance.clone().methods().get_withdrawal_fee(DEFAULT_VAULT).call().await.unwrap().value,
+        0
+    );
+    assert_eq!(
+        strategy_instance.clone().methods().get_withdrawal_fee(second_vault).call().await.unwrap().value,
+        300
+    );
+    assert_eq!(
+        strategy_instance.clone().methods().get_target_tokens(second_vault).call().await.unwrap().value.len(),
+        2
+    );
+
+    // Deposits mint each vault's own receipt asset
+    strategy_instance.clone()
+        .methods()
+        .deposit(Identity::Address(wallet.address().into()), second_vault)
+        .call_params(CallParameters::default()
+            .with_amount(40_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 10_000).await;
+
+    let default_receipt = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let second_receipt = strategy_instance.clone().methods().asset_id(second_vault).call().await.unwrap().value;
+    assert_ne!(default_receipt, second_receipt);
+    assert_eq!(wallet.get_asset_balance(&default_receipt).await.unwrap(), 10_000);
+    assert_eq!(wallet.get_asset_balance(&second_receipt).await.unwrap(), 40_000);
+
+    let second_supply = strategy_instance.clone().methods().total_supply(second_receipt).call().await.unwrap().value;
+    assert_eq!(second_supply, Some(40_000));
+    let second_name = strategy_instance.clone().methods().name(second_receipt).call().await.unwrap().value;
+    assert_eq!(second_name, Some("Sprout Stable".to_string()));
+
+    // Holdings are tracked per vault even though they share a balance
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(fuel_asset, 10_000)]);
+    let holdings = strategy_instance.clone().methods().get_basket_holdings(second_vault).call().await.unwrap().value;
+    assert_eq!(holdings, vec![(usdc_asset, 19_000), (fuel_asset, 20_000)]);
+    assert_eq!(contract_balance(&wallet, strategy_id, fuel_asset).await, 30_000);
+}
+
+#[tokio::test]
+async fn test_unknown_vault_is_rejected() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, _wallets) = get_contract_instance().await;
+    let unknown_vault = Bits256([9u8; 32]);
+
+    let result = strategy_instance.clone()
+        .methods()
+        .deposit(Identity::Address(Address::zeroed()), unknown_vault)
+        .call_params(CallParameters::default()
+            .with_amount(1_000)
+            .with_asset_id(AssetId::zeroed()))
+        .unwrap()
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::VaultNotFound(unknown_vault));
+
+    // The default vault can't be created twice
+    let result = strategy_instance.clone()
+        .methods()
+        .create_vault(
+            DEFAULT_VAULT,
+            "Again".to_string(),
+            "AGN".to_string(),
+            String::new(),
+            String::new(),
+        )
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::VaultAlreadyExists(DEFAULT_VAULT));
+}
+
+#[tokio::test]
+async fn test_vault_metadata_is_set_at_creation() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, _mira_id, _token_id, _wallets) = get_contract_instance().await;
+    let receipt_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+
+    // Variable-length metadata given to the constructor
+    let name = strategy_instance.clone().methods().name(receipt_asset).call().await.unwrap().value;
+    assert_eq!(name, Some(DEFAULT_VAULT_NAME.to_string()));
+    let symbol = strategy_instance.clone().methods().symbol(receipt_asset).call().await.unwrap().value;
+    assert_eq!(symbol, Some(DEFAULT_VAULT_SYMBOL.to_string()));
+
+    let description = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "description".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(description, Some(Metadata::String(DEFAULT_VAULT_DESCRIPTION.to_string())));
+    let icon_uri = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "icon_uri".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(icon_uri, Some(Metadata::String(DEFAULT_VAULT_ICON_URI.to_string())));
+
+    // The basket lists the underlying asset ids once allocations are set
+    let usdc_asset = init_usdc_only_allocation(&strategy_instance).await;
+    let basket = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "basket".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(basket, Some(Metadata::Bytes(Bytes(usdc_asset.to_vec()))));
+
+    // Unknown keys and assets have no metadata
+    let unknown = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "unknown".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(unknown, None);
+    let unknown = strategy_instance.clone()
+        .methods()
+        .metadata(AssetId::new([7u8; 32]), "description".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(unknown, None);
+}
+
+#[tokio::test]
+async fn test_create_vault_emits_src20_events() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, _mira_id, _token_id, _wallets) = get_contract_instance().await;
+    let vault_id = Bits256([2u8; 32]);
+
+    let response = strategy_instance.clone()
+        .methods()
+        .create_vault(
+            vault_id,
+            "Sprout Blue Chips".to_string(),
+            "sBLUE".to_string(),
+            "Large cap basket".to_string(),
+            "https://sprout.finance/icons/sblue.svg".to_string(),
+        )
+        .call()
+        .await
+        .unwrap();
+    let asset = strategy_instance.clone().methods().asset_id(vault_id).call().await.unwrap().value;
+
+    let names = response.decode_logs_with_type::<SetNameEvent>().unwrap();
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].asset, asset);
+    assert_eq!(names[0].name, Some("Sprout Blue Chips".to_string()));
+
+    let symbols = response.decode_logs_with_type::<SetSymbolEvent>().unwrap();
+    assert_eq!(symbols[0].symbol, Some("sBLUE".to_string()));
+
+    let decimals = response.decode_logs_with_type::<SetDecimalsEvent>().unwrap();
+    assert_eq!(decimals[0].decimals, 9);
+
+    let supply = response.decode_logs_with_type::<TotalSupplyEvent>().unwrap();
+    assert_eq!(supply[0].supply, 0);
+
+    let metadata = response.decode_logs_with_type::<SetMetadataEvent>().unwrap();
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(metadata[0].key, "description");
+}
+
+#[tokio::test]
+async fn test_metadata_tracks_allocations_and_fees() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, _wallets) = get_contract_instance().await;
+    let receipt_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = init_usdc_only_allocation(&strategy_instance).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    let weights = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "weights".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(weights, Some(Metadata::Bytes(Bytes(10000u64.to_be_bytes().to_vec()))));
+
+    // Rebalancing logs the new basket and weights
+    let response = strategy_instance.clone()
+        .methods()
+        .rebalance(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 2500 },
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 7500 },
+        ])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    let expected_weights = [2500u64.to_be_bytes(), 7500u64.to_be_bytes()].concat();
+    let expected_basket = [fuel_asset.to_vec(), usdc_asset.to_vec()].concat();
+    let events = response.decode_logs_with_type::<SetMetadataEvent>().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].key, "basket");
+    assert_eq!(events[0].metadata, Some(Metadata::Bytes(Bytes(expected_basket))));
+    assert_eq!(events[1].key, "weights");
+    assert_eq!(events[1].metadata, Some(Metadata::Bytes(Bytes(expected_weights.clone()))));
+
+    let weights = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "weights".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(weights, Some(Metadata::Bytes(Bytes(expected_weights))));
+
+    // Fee setters keep the fee parameters in sync
+    let response = strategy_instance.clone()
+        .methods()
+        .set_withdrawal_fee(DEFAULT_VAULT, 150)
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SetMetadataEvent>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].key, "withdrawal_fee");
+    assert_eq!(events[0].metadata, Some(Metadata::Int(150)));
+
+    let response = strategy_instance.clone()
+        .methods()
+        .update_slippage_tolerance(200)
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<SetMetadataEvent>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].metadata, Some(Metadata::Int(200)));
+
+    let slippage = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "slippage_tolerance".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(slippage, Some(Metadata::Int(200)));
+}
+
+#[tokio::test]
+async fn test_vault_uri() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, _mira_id, _token_id, wallets) = get_contract_instance().await;
+    let non_owner = wallets.get(1).unwrap().clone();
+    let receipt_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let uri = "https://sprout.finance/vaults/sfusd".to_string();
+
+    let result = strategy_instance.clone()
+        .with_account(non_owner.clone())
+        .methods()
+        .set_vault_uri(DEFAULT_VAULT, uri.clone())
+        .call()
+        .await;
+    assert_reverted_with(
+        result,
+        StrategyError::Unauthorized(Identity::Address(non_owner.address().into())),
+    );
+
+    strategy_instance.clone()
+        .methods()
+        .set_vault_uri(DEFAULT_VAULT, uri.clone())
+        .call()
+        .await
+        .unwrap();
+
+    let metadata = strategy_instance.clone()
+        .methods()
+        .metadata(receipt_asset, "uri".to_string())
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(metadata, Some(Metadata::String(uri)));
+}
+
+#[tokio::test]
+async fn test_src6_deposit_and_withdraw_for_receiver() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let depositor = wallets.get(0).unwrap().clone();
+    let receiver = wallets.get(1).unwrap().clone();
+    let receiver_identity = Identity::Address(receiver.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    // Nothing can be deposited into a vault without allocations
+    let unallocated = Bits256([4u8; 32]);
+    strategy_instance.clone()
+        .methods()
+        .create_vault(unallocated, "Empty".to_string(), "EMPTY".to_string(), String::new(), String::new())
+        .call()
+        .await
+        .unwrap();
+    let max_depositable = strategy_instance.clone()
+        .methods()
+        .max_depositable(receiver_identity, fuel_asset, unallocated)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(max_depositable, None);
+
+    // Shares go to the receiver, not the caller
+    let response = strategy_instance.clone()
+        .with_account(depositor.clone())
+        .methods()
+        .deposit(receiver_identity, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(25_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 25_000);
+
+    let events = response.decode_logs_with_type::<Deposit>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].caller, Identity::Address(depositor.address().into()));
+    assert_eq!(events[0].receiver, receiver_identity);
+    assert_eq!(events[0].vault_sub_id, DEFAULT_VAULT);
+    assert_eq!(events[0].minted_shares, 25_000);
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(receiver.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+    assert_eq!(depositor.get_asset_balance(&share_asset).await.unwrap(), 0);
+
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(fuel_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(managed, 25_000);
+    let max_withdrawable = strategy_instance.clone()
+        .methods()
+        .max_withdrawable(fuel_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(max_withdrawable, Some(25_000));
+
+    // The receiver redeems its shares, paying out to the depositor
+    let depositor_before = depositor.get_asset_balance(&fuel_asset).await.unwrap();
+    let response = strategy_instance.clone()
+        .with_account(receiver.clone())
+        .methods()
+        .withdraw(Identity::Address(depositor.address().into()), fuel_asset, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(10_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 10_000);
+
+    let events = response.decode_logs_with_type::<Withdraw>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].burned_shares, 10_000);
+    assert_eq!(events[0].withdrawn_amount, 10_000);
+
+    let depositor_after = depositor.get_asset_balance(&fuel_asset).await.unwrap();
+    assert_eq!(depositor_after - depositor_before, 10_000);
+    assert_eq!(contract_balance(&depositor, strategy_id, fuel_asset).await, 15_000);
+}
+
+#[tokio::test]
+async fn test_src6_accounting_follows_holdings() {
+    let (strategy_instance, _mira_instance, token_instance, _strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &wallet, mira_id, 1_000_000).await;
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+
+    // The USDC is valued at the pool's spot price, not at what was paid for it
+    let base_reserve = contract_balance(&wallet, mira_id, fuel_asset).await;
+    let usdc_reserve = contract_balance(&wallet, mira_id, usdc_asset).await;
+    let expected = 50_000 + 47_500 * base_reserve / usdc_reserve;
+
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(fuel_asset, DEFAULT_VAULT)
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(managed, expected);
+
+    // The withdrawal fee comes off what can be withdrawn
+    strategy_instance.clone()
+        .methods()
+        .set_withdrawal_fee(DEFAULT_VAULT, 100)
+        .call()
+        .await
+        .unwrap();
+    let max_withdrawable = strategy_instance.clone()
+        .methods()
+        .max_withdrawable(fuel_asset, DEFAULT_VAULT)
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(max_withdrawable, Some(expected - expected * 100 / SCALE));
+}
+
+#[tokio::test]
+async fn test_deposit_does_not_dilute_existing_holders() {
+    let (strategy_instance, _mira_instance, token_instance, _strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let holder = wallets.get(0).unwrap().clone();
+    let late_depositor = wallets.get(1).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+
+    // A shallow pool at two base per USDC, so every deposit moves its price
+    let usdc_asset = seed_usdc_pool(&token_instance, token_id, &holder, mira_id, 200_000).await;
+    holder
+        .force_transfer_to_contract(&mira_id.into(), 200_000, fuel_asset, TxPolicies::default())
+        .await
+        .unwrap();
+    let pool_id: (AssetId, AssetId, bool) = (fuel_asset, usdc_asset, false);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: usdc_asset, p_id: pool_id, percentage: 5000 },
+            TokenAllocation { token: fuel_asset, p_id: pool_id, percentage: 5000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+    let holdings_before = strategy_instance.clone().methods().get_basket_holdings(DEFAULT_VAULT).call().await.unwrap().value;
+
+    strategy_instance.clone()
+        .with_account(late_depositor.clone())
+        .methods()
+        .deposit(Identity::Address(late_depositor.address().into()), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(100_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    // The holder's 100_000 shares are still worth what the vault held before
+    // the deposit, valued at the prices the deposit left behind
+    let base_reserve = contract_balance(&holder, mira_id, fuel_asset).await;
+    let usdc_reserve = contract_balance(&holder, mira_id, usdc_asset).await;
+    let value_before: u64 = holdings_before
+        .iter()
+        .map(|(token, amount)| if *token == fuel_asset { *amount } else { amount * base_reserve / usdc_reserve })
+        .sum();
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let supply = strategy_instance.clone().methods().total_supply(share_asset).call().await.unwrap().value.unwrap();
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(fuel_asset, DEFAULT_VAULT)
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    let holder_value = (managed as u128 * 100_000 / supply as u128) as u64;
+    assert!(
+        holder_value.abs_diff(value_before) <= 1,
+        "holder's shares went from {value_before} to {holder_value}"
+    );
+}
+
+#[tokio::test]
+async fn test_burned_shares_raise_the_share_price() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let holder = wallets.get(0).unwrap().clone();
+    let late_depositor = wallets.get(1).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    strategy_instance.clone()
+        .methods()
+        .burn(DEFAULT_VAULT, 20_000)
+        .call_params(CallParameters::default()
+            .with_amount(20_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+
+    // The vault still holds everything, now backing fewer shares
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(fuel_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(managed, 100_000);
+
+    // So what's left of the share cap takes more of the base asset to fill
+    let max_depositable = strategy_instance.clone()
+        .methods()
+        .max_depositable(Identity::Address(late_depositor.address().into()), fuel_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(max_depositable, Some((MAX_SUPPLY - 80_000) / 4 * 5));
+
+    // A later deposit gets shares at the higher price
+    let minted = strategy_instance.clone()
+        .with_account(late_depositor.clone())
+        .methods()
+        .deposit(Identity::Address(late_depositor.address().into()), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(40_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(minted, 32_000);
+
+    // Both holders redeem at the same price
+    let paid = withdraw_shares(&strategy_instance, mira_id, Identity::Address(holder.address().into()), 80_000).await;
+    assert_eq!(paid, 100_000);
+    let paid = withdraw_shares(
+        &strategy_instance.clone().with_account(late_depositor.clone()),
+        mira_id,
+        Identity::Address(late_depositor.address().into()),
+        32_000,
+    )
+    .await;
+    assert_eq!(paid, 40_000);
+}
+
+#[tokio::test]
+async fn test_strategy_deposit_and_withdraw_pay_the_caller() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let user = wallets.get(1).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    let as_user = strategy_instance.clone().with_account(user.clone());
+    let share_asset = as_user.methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+
+    let minted = as_user
+        .methods()
+        .vault_deposit(DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(30_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(minted, 30_000);
+    assert_eq!(user.get_asset_balance(&share_asset).await.unwrap(), 30_000);
+
+    let response = as_user
+        .methods()
+        .vault_withdraw(DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(10_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 10_000);
+
+    // Same events as the SRC6 entry points
+    let events = response.decode_logs_with_type::<Withdraw>().unwrap();
+    assert_eq!(events[0].receiver, Identity::Address(user.address().into()));
+    assert_eq!(user.get_asset_balance(&share_asset).await.unwrap(), 20_000);
+    assert_eq!(contract_balance(&user, strategy_id, fuel_asset).await, 20_000);
+}
+
+#[tokio::test]
+async fn test_src6_withdraw_rejects_other_underlying_assets() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+    let other_asset = AssetId::new([5u8; 32]);
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 5_000).await;
+
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(other_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(managed, 0);
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let result = strategy_instance.clone()
+        .methods()
+        .withdraw(Identity::Address(wallet.address().into()), other_asset, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(1_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InvalidWithdrawalAsset(other_asset));
+}
+
+#[tokio::test]
+async fn test_native_receipts_are_minted_by_the_strategy() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_contract_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+    deposit_base(&strategy_instance, mira_id, 25_000).await;
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(share_asset, Bech32ContractId::from(strategy_id).asset_id(&DEFAULT_VAULT));
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+
+    // The token contract is left untouched
+    let token_asset = Bech32ContractId::from(token_id).asset_id(&DEFAULT_VAULT);
+    let token_supply = token_instance.methods().total_supply(token_asset).call().await.unwrap().value;
+    assert_eq!(token_supply, None);
+}
+
+#[tokio::test]
+async fn test_external_receipt_token_deposit_and_withdraw() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_external_receipt_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let wallet_identity = Identity::Address(wallet.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    // Receipts are the token contract's asset for the vault's sub-ID
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(share_asset, Bech32ContractId::from(token_id).asset_id(&DEFAULT_VAULT));
+
+    strategy_instance.clone()
+        .methods()
+        .deposit(wallet_identity, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(25_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into(), token_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+    let token_supply = token_instance.clone().methods().total_supply(share_asset).call().await.unwrap().value;
+    assert_eq!(token_supply, Some(25_000));
+    let strategy_supply = token_instance.clone().methods().strategy_supply(strategy_id).call().await.unwrap().value;
+    assert_eq!(strategy_supply, 25_000);
+
+    strategy_instance.clone()
+        .methods()
+        .withdraw(wallet_identity, fuel_asset, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(10_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[token_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    // The forwarded receipts were burned by the token contract
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 15_000);
+    assert_eq!(contract_balance(&wallet, strategy_id, share_asset).await, 0);
+    let token_supply = token_instance.clone().methods().total_supply(share_asset).call().await.unwrap().value;
+    assert_eq!(token_supply, Some(15_000));
+    let managed = strategy_instance.clone()
+        .methods()
+        .managed_assets(fuel_asset, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(managed, 15_000);
+}
+
+#[tokio::test]
+async fn test_external_receipt_token_is_locked() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, _mira_id, _token_id, _wallets) = get_external_receipt_instance().await;
+
+    let result = strategy_instance.clone()
+        .methods()
+        .set_sprout_receipt_token(ContractId::from([2u8; 32]))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::ReceiptTokenLocked);
+}
+
+#[tokio::test]
+async fn test_external_receipt_token_rejects_sibling_sub_id() {
+    let (strategy_instance, _mira_instance, token_instance, _strategy_id, mira_id, _token_id, wallets) = get_external_receipt_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let wallet_identity = Identity::Address(wallet.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    // The sub-ID is reserved for another strategy on the token
+    let sibling_vault = Bits256([7u8; 32]);
+    token_instance.clone()
+        .methods()
+        .assign_sub_id(ContractId::new([0x17; 32]), sibling_vault)
+        .call()
+        .await
+        .unwrap();
+
+    strategy_instance.clone()
+        .methods()
+        .create_vault(
+            sibling_vault,
+            "Sibling Basket".to_string(),
+            "sSIB".to_string(),
+            "Minted under a sibling's sub-ID".to_string(),
+            DEFAULT_VAULT_ICON_URI.to_string(),
+        )
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(sibling_vault, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    let result = strategy_instance.clone()
+        .methods()
+        .deposit(wallet_identity, sibling_vault)
+        .call_params(CallParameters::default()
+            .with_amount(25_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .with_contracts(&[&token_instance])
+        .call()
+        .await;
+    assert_reverted_with(result, FungibleError::SubIdNotAssigned(sibling_vault));
+}
+
+#[tokio::test]
+async fn test_external_receipt_token_enforces_strategy_supply_cap() {
+    let (strategy_instance, _mira_instance, token_instance, strategy_id, mira_id, token_id, wallets) = get_external_receipt_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let wallet_identity = Identity::Address(wallet.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    token_instance.clone()
+        .methods()
+        .set_strategy_supply_cap(strategy_id, Some(30_000))
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    let deposit = |amount: u64| {
+        strategy_instance.clone()
+            .methods()
+            .deposit(wallet_identity, DEFAULT_VAULT)
+            .call_params(CallParameters::default()
+                .with_amount(amount)
+                .with_asset_id(fuel_asset))
+            .unwrap()
+            .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+            .with_contract_ids(&[mira_id.into()])
+            .with_contracts(&[&token_instance])
+    };
+
+    deposit(25_000).call().await.unwrap();
+
+    // 35_000 shares would be outstanding
+    let result = deposit(10_000).call().await;
+    assert_reverted_with(result, FungibleError::StrategySupplyCapExceeded(30_000));
+
+    let share_asset = Bech32ContractId::from(token_id).asset_id(&DEFAULT_VAULT);
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+    let strategy_supply = token_instance.clone().methods().strategy_supply(strategy_id).call().await.unwrap().value;
+    assert_eq!(strategy_supply, 25_000);
+}
+
+// Deploys an external-receipt strategy whose receipt token is a Sprout token
+async fn get_sprout_token_instance() -> (
+    StrategyContract<WalletUnlocked>,
+    SproutToken<WalletUnlocked>,
+    ContractId,
+    ContractId,
+    ContractId,
+    Vec<WalletUnlocked>,
+) {
+    let wallets = launch_custom_provider_and_get_wallets(
+        WalletsConfig::new(Some(3), Some(1), Some(1_000_000_000)),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let wallet = wallets.get(0).unwrap().clone();
+
+    let sprout_id = Contract::load_from(
+        "contracts/sprout-token/out/debug/sprout-token.bin",
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .deploy(&wallet, TxPolicies::default())
+    .await
+    .unwrap();
+    let sprout_instance = SproutToken::new(sprout_id.clone(), wallet.clone());
+
+    let mira_id = Contract::load_from(
+        "contracts/mockMira/out/debug/mockMira.bin",
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .deploy(&wallet, TxPolicies::default())
+    .await
+    .unwrap();
+
+    let strategy_configurables = StrategyContractConfigurables::default()
+        .with_EXTERNAL_RECEIPT_TOKEN(true)
+        .unwrap()
+        .with_INITIAL_PROXY_OWNER(State::Initialized(Identity::Address(wallet.address().into())))
+        .unwrap();
+    let strategy_id = Contract::load_from(
+        "contracts/strategy/out/debug/strategy.bin",
+        LoadConfiguration::default().with_configurables(strategy_configurables),
+    )
+    .unwrap()
+    .deploy(&wallet, TxPolicies::default())
+    .await
+    .unwrap();
+    let strategy_instance = StrategyContract::new(strategy_id.clone(), wallet.clone());
+
+    // The strategy is the only contract allowed to mint and burn receipts
+    sprout_instance.methods().initialize_owner().call().await.unwrap();
+    sprout_instance
+        .methods()
+        .update_main_caller_contract(strategy_id.clone())
+        .call()
+        .await
+        .unwrap();
+
+    strategy_instance
+        .methods()
+        .constructor(
+            &sprout_id,
+            wallet.address(),
+            DEFAULT_VAULT_NAME.to_string(),
+            DEFAULT_VAULT_SYMBOL.to_string(),
+            DEFAULT_VAULT_DESCRIPTION.to_string(),
+            DEFAULT_VAULT_ICON_URI.to_string(),
+        )
+        .call()
+        .await
+        .unwrap();
+    strategy_instance.methods().initialize_owner().call().await.unwrap();
+    strategy_instance
+        .methods()
+        .set_mira_amm_contract(&mira_id)
+        .call()
+        .await
+        .unwrap();
+
+    (
+        strategy_instance,
+        sprout_instance,
+        strategy_id.into(),
+        mira_id.into(),
+        sprout_id.into(),
+        wallets,
+    )
+}
+
+#[tokio::test]
+async fn test_sprout_token_as_receipt_token() {
+    let (strategy_instance, sprout_instance, strategy_id, mira_id, sprout_id, wallets) = get_sprout_token_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let wallet_identity = Identity::Address(wallet.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    assert_eq!(share_asset, Bech32ContractId::from(sprout_id).asset_id(&DEFAULT_VAULT));
+
+    strategy_instance.clone()
+        .methods()
+        .deposit(wallet_identity, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(25_000)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into(), sprout_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+    let supply = sprout_instance.methods().total_supply(share_asset).call().await.unwrap().value;
+    assert_eq!(supply, Some(25_000));
+    let total_assets = sprout_instance.methods().total_assets().call().await.unwrap().value;
+    assert_eq!(total_assets, 1);
+    let name = sprout_instance.methods().name(share_asset).call().await.unwrap().value;
+    assert_eq!(name, Some("Sprout Token".to_string()));
+    let symbol = sprout_instance.methods().symbol(share_asset).call().await.unwrap().value;
+    assert_eq!(symbol, Some("SPRT".to_string()));
+    let decimals = sprout_instance.methods().decimals(share_asset).call().await.unwrap().value;
+    assert_eq!(decimals, Some(9));
+
+    strategy_instance.clone()
+        .methods()
+        .withdraw(wallet_identity, fuel_asset, DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(10_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[sprout_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(wallet.get_asset_balance(&share_asset).await.unwrap(), 15_000);
+    assert_eq!(contract_balance(&wallet, strategy_id, share_asset).await, 0);
+    let supply = sprout_instance.methods().total_supply(share_asset).call().await.unwrap().value;
+    assert_eq!(supply, Some(15_000));
+}
+
+#[tokio::test]
+async fn test_sprout_token_only_main_contract_mints() {
+    let (_strategy_instance, sprout_instance, strategy_id, _mira_id, _sprout_id, wallets) = get_sprout_token_instance().await;
+    let wallet = wallets.get(0).unwrap().clone();
+    let stranger = wallets.get(1).unwrap().clone();
+    let wallet_identity = Identity::Address(wallet.address().into());
+    let stranger_identity = Identity::Address(stranger.address().into());
+
+    let main_contract = sprout_instance.methods().main_contract().call().await.unwrap().value;
+    assert_eq!(main_contract, strategy_id);
+
+    // Not even the owner can mint directly
+    let result = sprout_instance
+        .methods()
+        .mint(wallet_identity, DEFAULT_VAULT, 1_000)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, SproutTokenError::Unauthorized(wallet_identity));
+
+    let result = sprout_instance
+        .clone()
+        .with_account(stranger.clone())
+        .methods()
+        .update_main_caller_contract(ContractId::from([2u8; 32]))
+        .call()
+        .await;
+    assert_reverted_with(result, SproutTokenError::Unauthorized(stranger_identity));
+
+    let response = sprout_instance
+        .methods()
+        .update_main_caller_contract(ContractId::from([2u8; 32]))
+        .call()
+        .await
+        .unwrap();
+    let events = response.decode_logs_with_type::<MainContractUpdated>().unwrap();
+    assert_eq!(
+        events,
+        vec![MainContractUpdated {
+            old_contract: strategy_id,
+            new_contract: ContractId::from([2u8; 32]),
+        }]
+    );
+}
+
+// Has `signer` sign a withdrawal of `owner`'s escrowed default-vault shares
+async fn sign_withdrawal(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    signer: &WalletUnlocked,
+    owner: &WalletUnlocked,
+    receiver: Identity,
+    amount: u64,
+    deadline: u64,
+    nonce: u64,
+) -> B512 {
+    let digest = strategy_instance.clone()
+        .methods()
+        .withdrawal_digest(owner.address().into(), receiver, DEFAULT_VAULT, amount, deadline, nonce)
+        .call()
+        .await
+        .unwrap()
+        .value;
+
+    let signature = signer.sign(Message::from_bytes(digest.0)).await.unwrap();
+    let bytes: [u8; 64] = *signature;
+    B512::from((
+        Bits256(bytes[..32].try_into().unwrap()),
+        Bits256(bytes[32..].try_into().unwrap()),
+    ))
+}
+
+// Deposits for `owner` and escrows `escrowed` of the shares for signed withdrawals
+async fn escrow_default_vault_shares(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    mira_id: ContractId,
+    owner: &WalletUnlocked,
+    deposited: u64,
+    escrowed: u64,
+) {
+    let fuel_asset = AssetId::zeroed();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+
+    let as_owner = strategy_instance.clone().with_account(owner.clone());
+    as_owner
+        .methods()
+        .deposit(Identity::Address(owner.address().into()), DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(deposited)
+            .with_asset_id(fuel_asset))
+        .unwrap()
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_contract_ids(&[mira_id.into()])
+        .call()
+        .await
+        .unwrap();
+
+    let share_asset = as_owner.methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    as_owner
+        .methods()
+        .escrow_shares(DEFAULT_VAULT)
+        .call_params(CallParameters::default()
+            .with_amount(escrowed)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_relayer_withdraws_with_signature() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let relayer = wallets.get(2).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    let fuel_asset = AssetId::zeroed();
+
+    escrow_default_vault_shares(&strategy_instance, mira_id, &owner, 25_000, 10_000).await;
+    let escrowed = strategy_instance.clone()
+        .methods()
+        .get_escrowed_shares(owner_identity, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(escrowed, 10_000);
+
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 10_000, u64::MAX, 0).await;
+
+    // The relayer pays for the transaction, the owner receives the base asset
+    let owner_before = owner.get_asset_balance(&fuel_asset).await.unwrap();
+    let response = strategy_instance.clone()
+        .with_account(relayer.clone())
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 10_000, u64::MAX, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 10_000);
+
+    let events = response.decode_logs_with_type::<SignedWithdrawal>().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, 10_000);
+    assert_eq!(events[0].nonce, 0);
+
+    let owner_after = owner.get_asset_balance(&fuel_asset).await.unwrap();
+    assert_eq!(owner_after - owner_before, 10_000);
+    assert_eq!(contract_balance(&owner, strategy_id, fuel_asset).await, 15_000);
+
+    let nonce = strategy_instance.clone().methods().get_nonce(owner_identity).call().await.unwrap().value;
+    assert_eq!(nonce, 1);
+
+    // The same signature can't be used twice
+    let result = strategy_instance.clone()
+        .with_account(relayer.clone())
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 10_000, u64::MAX, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InvalidNonce(1));
+}
+
+#[tokio::test]
+async fn test_withdraw_with_signature_rejects_invalid_requests() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let relayer = wallets.get(2).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    let relayer_identity = Identity::Address(relayer.address().into());
+
+    escrow_default_vault_shares(&strategy_instance, mira_id, &owner, 25_000, 10_000).await;
+    let as_relayer = strategy_instance.clone().with_account(relayer.clone());
+
+    // Signed by someone other than the owner
+    let signature = sign_withdrawal(&strategy_instance, &relayer, &owner, relayer_identity, 10_000, u64::MAX, 0).await;
+    let result = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), relayer_identity, DEFAULT_VAULT, 10_000, u64::MAX, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InvalidSignature);
+
+    // Signed for the owner but redirected to the relayer
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 10_000, u64::MAX, 0).await;
+    let result = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), relayer_identity, DEFAULT_VAULT, 10_000, u64::MAX, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InvalidSignature);
+
+    // Past its deadline
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 10_000, 0, 0).await;
+    let result = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 10_000, 0, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::SignatureExpired(0));
+
+    // More than the owner escrowed
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 20_000, u64::MAX, 0).await;
+    let result = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 20_000, u64::MAX, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::InsufficientEscrow(10_000));
+
+    // The owner can still take the escrowed shares back
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    strategy_instance.clone()
+        .with_account(owner.clone())
+        .methods()
+        .release_shares(DEFAULT_VAULT, 10_000)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(owner.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+}
+
+#[tokio::test]
+async fn test_signature_expires_at_a_near_deadline() {
+    let (strategy_instance, _mira_instance, _token_instance, _strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let relayer = wallets.get(2).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    let provider = owner.try_provider().unwrap().clone();
+
+    escrow_default_vault_shares(&strategy_instance, mira_id, &owner, 25_000, 20_000).await;
+    let as_relayer = strategy_instance.clone().with_account(relayer.clone());
+
+    // Deadlines are TAI64 like the block timestamp, a minute from now
+    let deadline = block_timestamp(&provider).await + 60;
+
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 10_000, deadline, 0).await;
+    let response = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 10_000, deadline, 0, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 10_000);
+
+    // Relayed only after the deadline has passed
+    let signature = sign_withdrawal(&strategy_instance, &owner, &owner, owner_identity, 10_000, deadline, 1).await;
+    advance_time(&provider, 120).await;
+    let result = as_relayer
+        .methods()
+        .withdraw_with_signature(owner.address().into(), owner_identity, DEFAULT_VAULT, 10_000, deadline, 1, signature)
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, StrategyError::SignatureExpired(deadline));
+
+    let escrowed = strategy_instance.clone()
+        .methods()
+        .get_escrowed_shares(owner_identity, DEFAULT_VAULT)
+        .call()
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(escrowed, 10_000);
+}
+
+const NAV_SCALE: u64 = 1_000_000_000;
+const LIMIT_ORDER_BIN: &str = "contracts/limit-order/out/debug/limit-order.bin";
+const LIMIT_ORDER_SCRIPT_BIN: &str = "contracts/limit-order-script/out/debug/limit-order-script.bin";
+// Fee budget and smallest fill of the orders placed by `fund_entry_order`
+const ORDER_MAX_FEE: u64 = 1_000_000;
+const ORDER_MIN_FILL: u64 = 10_000;
+
+// Locks `funds` of the base asset in an order for `owner` to enter the default vault
+async fn fund_entry_order(
+    strategy_instance: &StrategyContract<WalletUnlocked>,
+    strategy_id: ContractId,
+    owner: &WalletUnlocked,
+    nav_limit: u64,
+    funds: u64,
+) -> Predicate {
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let script_hash = Hasher::hash(std::fs::read(LIMIT_ORDER_SCRIPT_BIN).unwrap());
+
+    let configurables = LimitOrderPredicateConfigurables::default()
+        .with_OWNER(owner.address().into())
+        .unwrap()
+        .with_STRATEGY(strategy_id)
+        .unwrap()
+        .with_VAULT_ID(DEFAULT_VAULT)
+        .unwrap()
+        .with_SHARE_ASSET(share_asset)
+        .unwrap()
+        .with_NAV_LIMIT(nav_limit)
+        .unwrap()
+        .with_ORDER_SCRIPT_HASH(Bits256(*script_hash))
+        .unwrap()
+        .with_MAX_FEE(ORDER_MAX_FEE)
+        .unwrap()
+        .with_ORDER_AMOUNT(funds)
+        .unwrap()
+        .with_MIN_FILL(ORDER_MIN_FILL)
+        .unwrap();
+    let predicate = Predicate::load_from(LIMIT_ORDER_BIN)
+        .unwrap()
+        .with_provider(owner.try_provider().unwrap().clone())
+        .with_configurables(configurables);
+
+    owner
+        .transfer(predicate.address(), funds, AssetId::zeroed(), TxPolicies::default())
+        .await
+        .unwrap();
+
+    predicate
+}
+
+async fn init_base_only_allocation(strategy_instance: &StrategyContract<WalletUnlocked>) {
+    let fuel_asset = AssetId::zeroed();
+    strategy_instance.clone()
+        .methods()
+        .initialize_token_allocations(DEFAULT_VAULT, vec![
+            TokenAllocation { token: fuel_asset, p_id: (fuel_asset, fuel_asset, false), percentage: 10000 },
+        ])
+        .call()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_limit_order_predicate_deposits_at_limit() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    init_base_only_allocation(&strategy_instance).await;
+
+    // Shares are priced at par, so an order limited to par fills
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, NAV_SCALE, 50_000).await;
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+
+    let order = LimitOrder {
+        strategy: strategy_id,
+        vault_id: DEFAULT_VAULT,
+        share_asset,
+        receiver: owner_identity,
+        amount: 25_000,
+        nav_limit: NAV_SCALE,
+        exit: false,
+    };
+    let response = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(order)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 25_000);
+
+    assert_eq!(owner.get_asset_balance(&share_asset).await.unwrap(), 25_000);
+    assert_eq!(contract_balance(&owner, strategy_id, AssetId::zeroed()).await, 25_000);
+
+    // The rest, less the fee, stays locked for later fills
+    let remaining = predicate.get_asset_balance(&AssetId::zeroed()).await.unwrap();
+    assert!(remaining < 25_000 && remaining > 20_000);
+}
+
+#[tokio::test]
+async fn test_limit_order_predicate_waits_for_nav() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    init_base_only_allocation(&strategy_instance).await;
+
+    let nav_limit = NAV_SCALE - 1;
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, nav_limit, 50_000).await;
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+
+    let order = LimitOrder {
+        strategy: strategy_id,
+        vault_id: DEFAULT_VAULT,
+        share_asset,
+        receiver: owner_identity,
+        amount: 25_000,
+        nav_limit,
+        exit: false,
+    };
+    let result = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(order)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, LimitOrderError::NavAboveLimit(NAV_SCALE));
+    assert_eq!(owner.get_asset_balance(&share_asset).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_limit_order_predicate_rejects_other_receivers() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let filler = wallets.get(2).unwrap().clone();
+    init_base_only_allocation(&strategy_instance).await;
+
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, NAV_SCALE, 50_000).await;
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+
+    // A filler can't redirect the shares to themselves
+    let order = LimitOrder {
+        strategy: strategy_id,
+        vault_id: DEFAULT_VAULT,
+        share_asset,
+        receiver: Identity::Address(filler.address().into()),
+        amount: 25_000,
+        nav_limit: NAV_SCALE,
+        exit: false,
+    };
+    let result = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(order)
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert!(result.is_err(), "predicate unlocked for another receiver");
+
+    assert_eq!(filler.get_asset_balance(&share_asset).await.unwrap(), 0);
+    assert_eq!(predicate.get_asset_balance(&AssetId::zeroed()).await.unwrap(), 50_000);
+}
+
+#[tokio::test]
+async fn test_limit_order_predicate_rejects_dust_and_overpriced_fills() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    init_base_only_allocation(&strategy_instance).await;
+
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, NAV_SCALE, 50_000).await;
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    let order = |amount: u64| LimitOrder {
+        strategy: strategy_id,
+        vault_id: DEFAULT_VAULT,
+        share_asset,
+        receiver: owner_identity,
+        amount,
+        nav_limit: NAV_SCALE,
+        exit: false,
+    };
+
+    // Below the minimum fill
+    let result = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(order(ORDER_MIN_FILL - 1))
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert!(result.is_err(), "predicate unlocked for a dust fill");
+
+    // Half the order may only spend half the fee budget
+    let result = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(order(25_000))
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .with_tx_policies(TxPolicies::default().with_max_fee(ORDER_MAX_FEE / 2 + 1))
+        .call()
+        .await;
+    assert!(result.is_err(), "predicate unlocked for more than the fill's share of the fee");
+
+    assert_eq!(owner.get_asset_balance(&share_asset).await.unwrap(), 0);
+    assert_eq!(predicate.get_asset_balance(&AssetId::zeroed()).await.unwrap(), 50_000);
+}
+
+#[tokio::test]
+async fn test_limit_order_follows_nav_above_par() {
+    let (strategy_instance, _mira_instance, _token_instance, strategy_id, mira_id, _token_id, wallets) = get_contract_instance().await;
+    let owner = wallets.get(1).unwrap().clone();
+    let owner_identity = Identity::Address(owner.address().into());
+    init_base_only_allocation(&strategy_instance).await;
+
+    // Burning a fifth of the shares lifts the NAV to 1.25
+    deposit_base(&strategy_instance, mira_id, 100_000).await;
+    let share_asset = strategy_instance.clone().methods().asset_id(DEFAULT_VAULT).call().await.unwrap().value;
+    strategy_instance.clone()
+        .methods()
+        .burn(DEFAULT_VAULT, 20_000)
+        .call_params(CallParameters::default()
+            .with_amount(20_000)
+            .with_asset_id(share_asset))
+        .unwrap()
+        .call()
+        .await
+        .unwrap();
+    let nav = NAV_SCALE / 4 * 5;
+
+    // An order limited to par no longer fills
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, NAV_SCALE, 50_000).await;
+    let result = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(LimitOrder {
+            strategy: strategy_id,
+            vault_id: DEFAULT_VAULT,
+            share_asset,
+            receiver: owner_identity,
+            amount: 25_000,
+            nav_limit: NAV_SCALE,
+            exit: false,
+        })
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await;
+    assert_reverted_with(result, LimitOrderError::NavAboveLimit(nav));
+
+    // One limited to the new NAV fills at it
+    let predicate = fund_entry_order(&strategy_instance, strategy_id, &owner, nav, 50_000).await;
+    let response = LimitOrderScript::new(predicate.clone(), LIMIT_ORDER_SCRIPT_BIN)
+        .main(LimitOrder {
+            strategy: strategy_id,
+            vault_id: DEFAULT_VAULT,
+            share_asset,
+            receiver: owner_identity,
+            amount: 25_000,
+            nav_limit: nav,
+            exit: false,
+        })
+        .with_contract_ids(&[strategy_id.into(), mira_id.into()])
+        .with_variable_output_policy(VariableOutputPolicy::Exactly(1))
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(response.value, 20_000);
+    assert_eq!(owner.get_asset_balance(&share_asset).await.unwrap(), 20_000);
+}
@@ -0,0 +1,303 @@
+mod common;
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use common::storage_layout::{check_compatibility, field_key, ContractLayout, LayoutIssue};
+use serde_json::json;
+
+// Baselines of the currently deployed implementations. After deploying a new
+// one, refresh them with `UPDATE_LAYOUT_BASELINE=1 cargo test --test storage_layout`
+// and empty its known breaks.
+const UPDATE_BASELINE_ENV: &str = "UPDATE_LAYOUT_BASELINE";
+const STRATEGY_BASELINE: &str = "tests/fixtures/strategy-layout.json";
+const TREASURY_BASELINE: &str = "tests/fixtures/treasury-layout.json";
+
+/// Layout changes an implementation makes on purpose, by old field path and
+/// function name. They still need a new `IMPLEMENTATION_VERSION`.
+struct KnownBreaks {
+    slots: &'static [&'static str],
+    functions: &'static [&'static str],
+}
+
+// Version 2 moved all state into a namespace, keyed vault state by sub id and
+// took deposits and withdrawals to SRC6, so the version 1 deployment at 535db52
+// is migrated rather than upgraded in place.
+const STRATEGY_BREAKS: KnownBreaks = KnownBreaks {
+    slots: &[
+        "fee_treasury_contract",
+        "mira_amm_id",
+        "owner",
+        "slippage_tolerance",
+        "sprout_receipt_token",
+        "target_tokens",
+        "token_owner",
+        "total_supply",
+        "withdrawal_fee",
+    ],
+    functions: &[
+        "_set_proxy_owner",
+        "asset_id",
+        "constructor",
+        "deposit",
+        "emit_src20_events",
+        "get_target_tokens",
+        "get_token_allocation",
+        "get_withdrawal_fee",
+        "initialize_token_allocations",
+        "rebalance",
+        "set_sprout_receipt_token",
+        "set_withdrawal_fee",
+        "update_slippage_tolerance",
+        "withdraw",
+    ],
+};
+
+// Version 2 moved all state into a namespace and replaced the single strategy
+// with a set of authorized ones.
+const TREASURY_BREAKS: KnownBreaks = KnownBreaks {
+    slots: &["main_strategy_contract", "owner"],
+    functions: &["_set_proxy_owner", "get_strategy", "initialize_strategy", "set_strategy"],
+};
+
+const TARGET_KEY: &str = "7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55";
+
+const OLD_SOURCE: &str = r#"
+const IMPLEMENTATION_VERSION: u64 = 1;
+
+storage {
+    vault {
+        /// Shares minted so far
+        total_supply: u64 = 0,
+        owner: Option<Identity> = Option::None,
+        balances: StorageMap<Identity, u64> = StorageMap {},
+    },
+    target in 0x7bb458adc1d118713319a5baa00a2d049dd64d2916477d2688d76970c898cd55: Option<ContractId> = None,
+}
+"#;
+
+fn vault_slot(name: &str) -> String {
+    field_key(&["vault"], name)
+}
+
+fn old_slots() -> Vec<String> {
+    vec![vault_slot("total_supply"), vault_slot("owner"), TARGET_KEY.to_string()]
+}
+
+fn old_functions() -> Vec<(&'static str, &'static [&'static str], &'static str)> {
+    vec![
+        ("deposit", &["struct std::identity::Identity"], "u64"),
+        ("withdraw", &["struct std::identity::Identity"], "u64"),
+    ]
+}
+
+// Builds the layout forc would output for `source`, using each type string as
+// its own concrete type id
+fn layout(
+    source: &str,
+    slots: &[String],
+    functions: &[(&str, &[&str], &str)],
+) -> ContractLayout {
+    let slots = json!(slots
+        .iter()
+        .map(|key| json!({ "key": key, "value": "00" }))
+        .collect::<Vec<_>>());
+
+    let types: BTreeSet<&str> = functions
+        .iter()
+        .flat_map(|(_, inputs, output)| inputs.iter().chain([output]).copied())
+        .collect();
+    let abi = json!({
+        "concreteTypes": types
+            .iter()
+            .map(|ty| json!({ "type": ty, "concreteTypeId": ty }))
+            .collect::<Vec<_>>(),
+        "functions": functions
+            .iter()
+            .map(|(name, inputs, output)| json!({
+                "name": name,
+                "inputs": inputs
+                    .iter()
+                    .map(|ty| json!({ "name": "arg", "concreteTypeId": ty }))
+                    .collect::<Vec<_>>(),
+                "output": output,
+                "attributes": [{ "name": "storage", "arguments": ["read", "write"] }],
+            }))
+            .collect::<Vec<_>>(),
+    });
+
+    ContractLayout::from_compiled(source, &slots, &abi)
+}
+
+fn old_layout() -> ContractLayout {
+    layout(OLD_SOURCE, &old_slots(), &old_functions())
+}
+
+fn subjects<'a>(issues: impl Iterator<Item = &'a LayoutIssue>) -> BTreeSet<&'a str> {
+    issues
+        .map(|issue| match issue {
+            LayoutIssue::MissingSlot { field } | LayoutIssue::ChangedSlot { field, .. } => {
+                field.as_str()
+            }
+            LayoutIssue::MissingFunction { name } | LayoutIssue::ChangedFunction { name } => {
+                name.as_str()
+            }
+        })
+        .collect()
+}
+
+fn assert_upgradable_from_baseline(baseline: &str, name: &str, known: &KnownBreaks) {
+    let source = fs::read_to_string(format!("contracts/{name}/src/main.sw")).unwrap();
+    let new = ContractLayout::load(format!("contracts/{name}/out/debug"), name, &source);
+
+    if std::env::var(UPDATE_BASELINE_ENV).is_ok() {
+        new.save(baseline);
+        return;
+    }
+    assert!(
+        Path::new(baseline).exists(),
+        "{baseline} is missing, write it with {UPDATE_BASELINE_ENV}=1"
+    );
+    let old = ContractLayout::read(baseline);
+
+    let issues = check_compatibility(&old, &new);
+    let (slot_issues, function_issues): (Vec<_>, Vec<_>) = issues.iter().partition(|issue| {
+        matches!(issue, LayoutIssue::MissingSlot { .. } | LayoutIssue::ChangedSlot { .. })
+    });
+    // Equal rather than a subset, so breaks are dropped once the baseline moves past them
+    assert_eq!(
+        subjects(slot_issues.into_iter()),
+        known.slots.iter().copied().collect(),
+        "{name} storage breaks differ from the known ones: {issues:?}"
+    );
+    assert_eq!(
+        subjects(function_issues.into_iter()),
+        known.functions.iter().copied().collect(),
+        "{name} function breaks differ from the known ones: {issues:?}"
+    );
+
+    // Any change, breaking or not, needs a new version to be deployed under
+    if (&old.fields, &old.functions) != (&new.fields, &new.functions) || !issues.is_empty() {
+        assert!(
+            new.version > old.version,
+            "{name} changed its layout without bumping IMPLEMENTATION_VERSION past {}",
+            old.version
+        );
+    }
+}
+
+#[test]
+fn compiled_slots_are_typed_from_source() {
+    let layout = old_layout();
+
+    assert_eq!(layout.version, 1);
+    assert_eq!(layout.slots, old_slots().into_iter().collect());
+    assert_eq!(
+        layout.fields.keys().collect::<Vec<_>>(),
+        vec!["target", "vault.balances", "vault.owner", "vault.total_supply"]
+    );
+    assert_eq!(layout.fields["target"].key, TARGET_KEY);
+    assert_eq!(layout.fields["vault.total_supply"].key, vault_slot("total_supply"));
+    assert_eq!(layout.fields["vault.owner"].ty, "Option<Identity>");
+    assert_eq!(layout.functions["deposit"].inputs, vec!["Identity".to_string()]);
+}
+
+#[test]
+#[should_panic(expected = "`vault.owner` has no compiled slot")]
+fn source_must_agree_with_compiled_slots() {
+    let slots = vec![vault_slot("total_supply"), TARGET_KEY.to_string()];
+
+    layout(OLD_SOURCE, &slots, &old_functions());
+}
+
+#[test]
+fn identical_layouts_are_compatible() {
+    assert!(check_compatibility(&old_layout(), &old_layout()).is_empty());
+}
+
+#[test]
+fn layout_survives_the_baseline_round_trip() {
+    let old = old_layout();
+
+    assert_eq!(ContractLayout::from_json(&old.to_json()), old);
+}
+
+#[test]
+fn appended_slots_and_functions_are_compatible() {
+    let source = OLD_SOURCE.replace(
+        "        owner: Option<Identity> = Option::None,\n",
+        "        owner: Option<Identity> = Option::None,\n        paused: bool = false,\n",
+    );
+    let mut slots = old_slots();
+    slots.push(vault_slot("paused"));
+    let mut functions = old_functions();
+    functions.push(("paused", &[], "bool"));
+
+    assert!(check_compatibility(&old_layout(), &layout(&source, &slots, &functions)).is_empty());
+}
+
+#[test]
+fn removed_slot_is_flagged() {
+    // e.g. `total_supply` moved out of its namespace
+    let source = OLD_SOURCE
+        .replace("        total_supply: u64 = 0,\n", "")
+        .replace("storage {\n", "storage {\n    total_supply: u64 = 0,\n");
+    let slots = vec![field_key(&[], "total_supply"), vault_slot("owner"), TARGET_KEY.to_string()];
+
+    assert_eq!(
+        check_compatibility(&old_layout(), &layout(&source, &slots, &old_functions())),
+        vec![LayoutIssue::MissingSlot { field: "vault.total_supply".to_string() }]
+    );
+}
+
+#[test]
+fn removed_collection_is_flagged() {
+    // Maps get no compiled slot, only the source knows about them
+    let source = OLD_SOURCE.replace(
+        "        balances: StorageMap<Identity, u64> = StorageMap {},\n",
+        "",
+    );
+
+    assert_eq!(
+        check_compatibility(&old_layout(), &layout(&source, &old_slots(), &old_functions())),
+        vec![LayoutIssue::MissingSlot { field: "vault.balances".to_string() }]
+    );
+}
+
+#[test]
+fn changed_slot_type_is_flagged() {
+    // The key is derived from the path alone, so only the source tells these apart
+    let source = OLD_SOURCE.replace("total_supply: u64 = 0", "total_supply: u256 = 0");
+
+    assert_eq!(
+        check_compatibility(&old_layout(), &layout(&source, &old_slots(), &old_functions())),
+        vec![LayoutIssue::ChangedSlot {
+            field: "vault.total_supply".to_string(),
+            old: "u64".to_string(),
+            new: "u256".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn removed_and_changed_functions_are_flagged() {
+    let functions: Vec<(&str, &[&str], &str)> =
+        vec![("deposit", &["struct std::address::Address"], "u64")];
+
+    assert_eq!(
+        check_compatibility(&old_layout(), &layout(OLD_SOURCE, &old_slots(), &functions)),
+        vec![
+            LayoutIssue::ChangedFunction { name: "deposit".to_string() },
+            LayoutIssue::MissingFunction { name: "withdraw".to_string() },
+        ]
+    );
+}
+
+#[test]
+fn strategy_upgradable_from_baseline() {
+    assert_upgradable_from_baseline(STRATEGY_BASELINE, "strategy", &STRATEGY_BREAKS);
+}
+
+#[test]
+fn treasury_upgradable_from_baseline() {
+    assert_upgradable_from_baseline(TREASURY_BASELINE, "treasury", &TREASURY_BREAKS);
+}